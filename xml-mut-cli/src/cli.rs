@@ -1,22 +1,95 @@
-use clap::{Args, Parser, Subcommand};
+use crate::{output::Output, report::ReportFormat};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use walkdir::WalkDir;
+use xml_mut_data::Bindings;
+use xml_mut_xot::prelude::{CanonicalOptions, PrettyOptions, SerializeOptions};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(name = "xml-mut", author, version, about, long_about = None)]
 #[command(propagate_version = true)]
+#[command(subcommand_precedence_over_arg = true)]
 pub struct MutCli {
-    /// path to xml mutation file, usually with a .xut file extension
-    pub xml_mut_path: std::path::PathBuf,
+    /// paths to xml mutation files, usually with a .xmlmut file extension,
+    /// statements run in the order the files are given.
+    /// `@name` is a named rule file found on the rule search path
+    pub xml_mut_paths: Vec<std::path::PathBuf>,
+    /// how mutated xml is written back
+    #[arg(short, long, value_enum, default_value_t = Format::Serialize, global = true)]
+    pub format: Format,
+    /// write mutated xml as utf-8 without byte order mark and with lf line endings
+    #[arg(short, long, global = true)]
+    pub normalize: bool,
+    /// keep new elements inline instead of indenting them like their siblings
+    #[arg(long, global = true)]
+    pub no_indent: bool,
+    /// number of spaces per indentation level of pretty format
+    #[arg(long, default_value_t = 2, global = true)]
+    pub indent: usize,
+    /// pretty format puts each attribute on its own line when an element has more
+    #[arg(long, global = true)]
+    pub attributes_per_line: Option<usize>,
+    /// how values selected by queries are printed
+    #[arg(short, long, value_enum, default_value_t = Output::Text, global = true)]
+    pub output: Output,
+    /// value of a `$name` parameter as `name=value` (can be multiple)
+    #[arg(short = 'D', long, global = true, value_parser = parse_definition, action = clap::ArgAction::Append)]
+    pub define: Vec<(String, String)>,
+    /// run only the statement with this name (can be multiple)
+    #[arg(long, global = true, action = clap::ArgAction::Append)]
+    pub only: Vec<String>,
+    /// skip the statement with this name (can be multiple)
+    #[arg(long, global = true, action = clap::ArgAction::Append)]
+    pub skip: Vec<String>,
+    /// list nodes matched by mutations instead of writing files,
+    /// fails when any node matches
+    #[arg(long, global = true)]
+    pub check: bool,
+    /// print statistics of the run per file and per statement
+    #[arg(long, value_enum, global = true)]
+    pub report: Option<ReportFormat>,
+    /// write the report to this file instead of standard output
+    #[arg(long, global = true, requires = "report")]
+    pub report_file: Option<std::path::PathBuf>,
     #[command(subcommand)]
     pub xmls: Commands,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// serialize the whole mutated xml tree
+    Serialize,
+    /// patch only the changed parts of the original xml text
+    Lossless,
+    /// indent the whole xml
+    Pretty,
+    /// canonical xml 1.0
+    C14n,
+    /// exclusive xml canonicalization 1.0
+    ExcC14n,
+}
+
+impl Format {
+    /// Whether every file is rewritten, mutated or not
+    pub fn normalizes(&self) -> bool {
+        matches!(self, Format::Pretty | Format::C14n | Format::ExcC14n)
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// scan for xml files in a directory
     Scan(ScanArgs),
     /// include multiple xml files
     Include(IncludeArgs),
+    /// named rule files found on the rule search path
+    #[command(subcommand)]
+    Rules(RulesCommands),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RulesCommands {
+    /// list rule names with the first comment of each file
+    List,
 }
 
 #[derive(Args, Debug)]
@@ -35,11 +108,50 @@ pub struct IncludeArgs {
     pub xml_path: Vec<std::path::PathBuf>,
 }
 
+fn parse_definition(definition: &str) -> Result<(String, String), String> {
+    match definition.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("expected name=value, found '{}'", definition)),
+    }
+}
+
 impl MutCli {
+    /// Parameter values given with `--define`
+    pub fn bindings(&self) -> Bindings {
+        let mut bindings = Bindings::default();
+        for (name, value) in &self.define {
+            bindings.insert(name, value);
+        }
+        bindings
+    }
+
+    /// Whether a statement with this name is run, see `--only` and `--skip`
+    pub fn runs(&self, name: Option<&str>) -> bool {
+        let listed = |names: &[String]| name.is_some_and(|n| names.iter().any(|o| o == n));
+        (self.only.is_empty() || listed(&self.only)) && !listed(&self.skip)
+    }
+
+    /// Serialization of a mutated xml tree, `None` for lossless patching
+    pub fn serialize_options(&self) -> Option<SerializeOptions> {
+        match self.format {
+            Format::Serialize => Some(SerializeOptions::Plain),
+            Format::Lossless => None,
+            Format::Pretty => Some(SerializeOptions::Pretty(PrettyOptions {
+                indent: " ".repeat(self.indent),
+                attributes_per_line: self.attributes_per_line,
+            })),
+            Format::C14n | Format::ExcC14n => Some(SerializeOptions::Canonical(CanonicalOptions {
+                exclusive: self.format == Format::ExcC14n,
+                with_comments: false,
+            })),
+        }
+    }
+
     pub fn scan(&self) -> Vec<std::path::PathBuf> {
         match &self.xmls {
             Commands::Scan(s) => s.scan(),
             Commands::Include(p) => p.xml_path.clone(),
+            Commands::Rules(_) => vec![],
         }
     }
 }
@@ -1,81 +1,314 @@
-use crate::cli::MutCli;
-use clap::Parser;
-use std::fs;
-use xml_mut_data::{Mutation, Statement};
+use crate::{
+    cli::{Commands, Format, MutCli, RulesCommands},
+    mutation_file::MutationFiles,
+    output::{Output, Row},
+    report::{FileReport, RemovedDuplicate, Report},
+    rules::RuleLibrary,
+    source::XmlSource,
+    write::write_if_changed,
+};
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::Instant,
+};
+use xml_mut_data::{Bindings, Mutation};
 use xml_mut_parse::prelude::*;
-use xml_mut_xot::prelude::Valueable;
-use xot::Xot;
+use xml_mut_xot::prelude::{
+    resolve, Fitable, Locatable, Operation, Patchable, Selectable, Serializable, Valueable,
+};
+use xot::{Node, Xot};
 
 mod cli;
+mod mutation_file;
+mod output;
+mod report;
+mod rules;
+mod sarif;
+mod source;
+mod write;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // TODO: a name of mutation defination file in a common folder ~/.xml-mut/
-
+fn main() -> Result<(), Box<dyn Error>> {
     let mut_cli = MutCli::parse();
+    let library = RuleLibrary::from_env();
 
-    let xut = fs::read_to_string(mut_cli.xml_mut_path.clone())?;
+    if let Commands::Rules(RulesCommands::List) = mut_cli.xmls {
+        return list_rules(&library);
+    }
+    if mut_cli.xml_mut_paths.is_empty() {
+        MutCli::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "at least one <XML_MUT_PATHS> is required",
+            )
+            .exit();
+    }
 
-    let (non_parsed, ref grammar) =
-        xml_mut_grammar(xut.as_str()).expect("could not parse statements");
+    let xml_mut_paths = library.expand(&mut_cli.xml_mut_paths)?;
+    let mutation_files = MutationFiles::load(&xml_mut_paths)?;
+    let grammar = &mutation_files.grammar()?;
 
-    let mutations = &grammar
-        .statements
-        .iter()
-        .filter_map(|s| match s {
-            Statement::Mutation(rep) => Some(rep),
-            _ => None,
-        })
-        .collect::<Vec<&Mutation>>();
-
-    if !non_parsed.is_empty() {
-        println!(
-            "Parsed xml mut out of '{:?}', but there is still remainder left: \n{}",
-            mut_cli.xml_mut_path, non_parsed
+    // a parameter is reported in the file it is used in
+    let mut unbound = 0;
+    for file in &mutation_files.files {
+        for parameter in unbound_parameters(&file.source, grammar, &mut_cli.bindings())
+            .into_iter()
+            .filter(|p| p.location.is_some())
+        {
+            eprintln!(
+                "{:?}: {}, bind it with --define {}=...",
+                file.path, parameter, parameter.name
+            );
+            unbound += 1;
+        }
+    }
+    if unbound > 0 {
+        return Err(format!("{} parameter(s) are not bound.", unbound).into());
+    }
+    let mut unknown = 0;
+    for file in &mutation_files.files {
+        for predicate in unknown_predicates(&file.source, grammar)
+            .into_iter()
+            .filter(|p| p.location.is_some())
+        {
+            eprintln!("{:?}: {}", file.path, predicate);
+            unknown += 1;
+        }
+    }
+    if unknown > 0 {
+        return Err(format!("{} predicate(s) are not defined.", unknown).into());
+    }
+    let resolved = resolve(grammar, &mut_cli.bindings()).map_err(|err| err.to_string())?;
+    let bindings = resolved.bindings;
+    let mutations = resolved.mutations.iter().collect::<Vec<&Mutation>>();
+
+    for name in mut_cli.only.iter().chain(&mut_cli.skip) {
+        if !mutations.iter().any(|m| m.name == Some(name.as_str())) {
+            return Err(format!("There is no statement named '{}'.", name).into());
+        }
+    }
+    // labels keep the position in the file even when statements are skipped
+    let (labels, mutations): (Vec<String>, Vec<&Mutation>) = mutations
+        .into_iter()
+        .enumerate()
+        .filter(|(_, m)| mut_cli.runs(m.name))
+        .map(|(i, m)| (label(m, i), m))
+        .unzip();
+
+    // rows and the report would end up in one stream that is no longer json
+    let prints_rows = mut_cli.check
+        || mut_cli.output == Output::Sarif
+        || mutations.iter().any(|m| m.select_clause.is_some());
+    if mut_cli.report.is_some() && mut_cli.report_file.is_none() && prints_rows {
+        return Err(
+            "The report and the selected rows both go to standard output, \
+             write the report with --report-file."
+                .into(),
         );
     }
 
-    println!("{} mutation(s) to be applied.", mutations.len());
-    for xml_path in mut_cli.scan().iter() {
-        let xml = fs::read_to_string(xml_path.clone())?;
-        let mut xot = Xot::new();
-        let root = xot.parse(xml.as_str()).map_err(xot::Error::from)?;
-        let doc_element_node = xot.document_element(root)?;
-        let ops = xot.get_operations_all(doc_element_node, mutations)?;
+    // progress goes to stderr, stdout is left for query output and reports
+    eprintln!(
+        "{} mutation(s) to be applied.",
+        mutations.iter().filter(|m| !m.is_query()).count()
+    );
+    let started = Instant::now();
+    let mut report = Report::default();
+    let mut rows = vec![];
+    for xml_path in mut_cli.scan() {
+        let started = Instant::now();
+        let mut file = FileReport::new(xml_path.clone(), &labels);
+        if let Err(err) = mutate_file(
+            &mut_cli, &mutations, &bindings, &xml_path, &mut file, &mut rows,
+        ) {
+            eprintln!("{:?} - failed: {}", xml_path, err);
+            file.error = Some(err.to_string());
+        }
+        file.duration = started.elapsed();
+        report.files.push(file);
+    }
+    report.duration = started.elapsed();
+    let duplicates = report.removed_duplicates();
+    if duplicates > 0 && !mut_cli.check {
+        eprintln!("{} duplicate(s) removed.", duplicates);
+    }
 
-        if !ops.is_empty() {
-            println!(
-                "{} operation(s) to be applied for {:?}",
-                ops.len(),
-                xml_path
-            );
-            xot.apply_all(&ops)?;
-            let mut xml_file = fs::File::create(xml_path)?;
-            xot.serialize_xml_write(Default::default(), root, &mut xml_file)?;
-            // TODO: count number of mutations applied
-            println!("{:?} - updated", xml_path);
+    // a lint run without findings is still a valid sarif log
+    if !rows.is_empty() || mut_cli.output == Output::Sarif {
+        mut_cli.output.write(&rows, io::stdout().lock())?;
+    }
+    if let Some(format) = mut_cli.report {
+        match &mut_cli.report_file {
+            Some(path) => report.write(format, fs::File::create(path)?)?,
+            None => report.write(format, io::stdout().lock())?,
+        }
+    }
+
+    let findings: usize = report
+        .files
+        .iter()
+        .flat_map(|f| f.statements.iter().zip(mutations.iter()))
+        .filter(|(_, m)| !m.is_query())
+        .map(|(s, _)| s.nodes_matched)
+        .sum();
+    match report.failed_files() {
+        0 if mut_cli.check && findings > 0 => {
+            Err(format!("{} node(s) matched by mutations", findings).into())
         }
+        0 => Ok(()),
+        failed => Err(format!("{} file(s) failed", failed).into()),
     }
+}
 
+/// Prints rule names usable as `@name` with their descriptions
+fn list_rules(library: &RuleLibrary) -> Result<(), Box<dyn Error>> {
+    let rules = library.list()?;
+    if rules.is_empty() {
+        let searched: Vec<String> = library
+            .dirs
+            .iter()
+            .map(|d| d.display().to_string())
+            .collect();
+        eprintln!("No rules found, searched: {}.", searched.join(", "));
+    }
+    let width = rules.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    let mut stdout = io::stdout().lock();
+    for rule in rules {
+        let line = format!("@{:width$}  {}", rule.name, rule.description);
+        writeln!(stdout, "{}", line.trim_end())?;
+    }
     Ok(())
 }
 
-// fn get_nodes_as_text(
-//     paths: Vec<std::path::PathBuf>,
-//     mutations: &[&Mutation],
-// ) -> Result<String, Box<dyn std::error::Error>> {
-//     let mut nodes: Vec<String> = Vec::new();
-
-//     for xml_path in paths {
-//         let xml = fs::read_to_string(xml_path.clone())?;
-//         let doc: Document = Document::parse(xml.as_str())?;
-//         let mut found = doc.get_fit_nodes_all(mutations);
-//         println!("{} found in {:?}", found.len(), xml_path,);
-//         nodes.append(&mut found);
-//     }
-
-//     Ok(nodes
-//         .iter()
-//         .unique_by(|p| *p)
-//         .sorted_by(|a, b| Ord::cmp(&a, &b))
-//         .join("\n"))
-// }
+/// Name of a statement in messages and reports, the position in the
+/// mutation file if it has no name
+fn label(mutation: &Mutation, index: usize) -> String {
+    match mutation.name {
+        Some(name) => name.to_string(),
+        None => format!("statement-{}", index + 1),
+    }
+}
+
+/// Runs all statements on a single xml file, writing it when it changes
+fn mutate_file(
+    mut_cli: &MutCli,
+    mutations: &[&Mutation],
+    bindings: &Bindings,
+    xml_path: &Path,
+    file: &mut FileReport,
+    rows: &mut Vec<Row>,
+) -> Result<(), Box<dyn Error>> {
+    let xml_bytes = fs::read(xml_path)?;
+    let mut source = XmlSource::decode(&xml_bytes)?;
+    if mut_cli.normalize {
+        source = source.normalized();
+    }
+    if matches!(mut_cli.format, Format::C14n | Format::ExcC14n) {
+        // canonical xml is utf-8 with lf line endings and no declaration
+        source = source.normalized();
+        source.declaration = None;
+    }
+    let xml = source.text.as_str();
+    let mut xot = Xot::new();
+    let (root, span_info) = xot.parse_with_span_info(xml).map_err(xot::Error::from)?;
+    let doc_element_node = xot.document_element(root)?;
+
+    let mut ops = vec![];
+    for (&mutation, statement) in mutations.iter().zip(file.statements.iter_mut()) {
+        let started = Instant::now();
+        let nodes: Vec<Node> = xot
+            .descendants(doc_element_node)
+            .filter(|n| !xot.is_dropped(*n, &ops) && xot.is_fit(*n, mutation, bindings))
+            .collect();
+        statement.nodes_matched = nodes.len();
+
+        let row = |node: Node, values| Row {
+            path: xml_path.to_path_buf(),
+            element: xot.element_path(node),
+            region: xot.region(xml, &span_info, node).unwrap_or_default(),
+            rule: statement.label.clone(),
+            values,
+        };
+        match &mutation.select_clause {
+            Some(select_clause) => rows.extend(
+                nodes
+                    .iter()
+                    .map(|n| row(*n, xot.select(*n, select_clause).values)),
+            ),
+            // in check mode every node a mutation matches is a finding
+            None if mut_cli.check => rows.extend(nodes.iter().map(|n| row(*n, vec![]))),
+            None => (),
+        }
+
+        // statements see what the ones before them remove
+        match xot.get_operations_after(doc_element_node, &[mutation], bindings, &ops) {
+            Ok(mut statement_ops) => {
+                if !statement_ops.is_empty() && !mut_cli.check {
+                    eprintln!(
+                        "{} operation(s) of {} to be applied for {:?}",
+                        statement_ops.len(),
+                        statement.label,
+                        xml_path
+                    );
+                }
+                statement.count(&statement_ops);
+                for op in &statement_ops {
+                    if let Operation::DeleteDuplicate(op) = op {
+                        let region = xot.region(xml, &span_info, op.node).unwrap_or_default();
+                        statement.duplicates.push(RemovedDuplicate {
+                            element: xot.element_path(op.node),
+                            line: region.start_line,
+                            column: region.start_column,
+                        });
+                    }
+                }
+                ops.append(&mut statement_ops);
+            }
+            Err(err) => statement.error = Some(err.to_string()),
+        }
+        statement.duration = started.elapsed();
+    }
+    if let Some(statement) = file.statements.iter().find(|s| s.error.is_some()) {
+        // a file is either mutated by all statements or left as it is
+        let err = statement.error.as_deref().unwrap_or_default();
+        return Err(format!("{}: {}", statement.label, err).into());
+    }
+    if mut_cli.check || mutations.iter().all(|m| m.is_query()) {
+        return Ok(());
+    }
+    for duplicate in file.statements.iter().flat_map(|s| &s.duplicates) {
+        eprintln!(
+            "{}:{}:{} - duplicate {} removed",
+            xml_path.display(),
+            duplicate.line,
+            duplicate.column,
+            duplicate.element
+        );
+    }
+    if mut_cli.no_indent {
+        ops = ops.into_iter().map(Operation::unindented).collect();
+    }
+
+    if !ops.is_empty() || mut_cli.format.normalizes() {
+        let xml_new = match mut_cli.serialize_options() {
+            Some(options) => {
+                // the tree does not know which text was written as CDATA
+                let cdata = xot.cdata_nodes(xml, &span_info, root);
+                xot.apply_all(&ops)?;
+                xot.serialize_with_cdata(root, &options, &cdata)?
+            }
+            None => xot.patch_all(xml, &span_info, &ops)?,
+        };
+        file.written = write_if_changed(xml_path, &xml_bytes, &source.encode(&xml_new))?;
+        if file.written {
+            eprintln!("{:?} - updated", xml_path);
+        } else {
+            eprintln!("{:?} - unchanged", xml_path);
+        }
+    }
+
+    Ok(())
+}
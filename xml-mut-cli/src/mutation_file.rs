@@ -0,0 +1,168 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+use xml_mut_data::{Statement, XmlMutGrammar};
+use xml_mut_parse::prelude::*;
+
+/// A mutation file as read from disk
+#[derive(Debug)]
+pub struct MutationFile {
+    pub path: PathBuf,
+    pub source: String,
+    canonical: PathBuf,
+    /// Indexes of included files, in the order of `INCLUDE` statements
+    includes: Vec<usize>,
+}
+
+/// Mutation files given on the command line together with all the files
+/// they include. Every file is read once, however many times it is included.
+#[derive(Debug, Default)]
+pub struct MutationFiles {
+    pub files: Vec<MutationFile>,
+    roots: Vec<usize>,
+}
+
+/// Statements of a file and the text left after them
+fn parse(file: &MutationFile) -> Result<(&str, XmlMutGrammar<'_>), Box<dyn Error>> {
+    xml_mut_grammar(&file.source).map_err(|err| {
+        let at = error_location(&file.source, &err)
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "the end".to_string());
+        format!(
+            "'{}': could not parse statements at {}",
+            file.path.display(),
+            at
+        )
+        .into()
+    })
+}
+
+fn position(source: &str, fragment: &str) -> String {
+    location(source, fragment)
+        .map(|l| l.to_string())
+        .unwrap_or_else(|| "the end".to_string())
+}
+
+impl MutationFiles {
+    pub fn load(paths: &[PathBuf]) -> Result<Self, Box<dyn Error>> {
+        let mut files = MutationFiles::default();
+        for path in paths {
+            let index = files.load_file(path, &mut vec![])?;
+            files.roots.push(index);
+        }
+        Ok(files)
+    }
+
+    /// `including` holds the chain of files that led to this one
+    fn load_file(
+        &mut self,
+        path: &Path,
+        including: &mut Vec<PathBuf>,
+    ) -> Result<usize, Box<dyn Error>> {
+        let canonical = fs::canonicalize(path)
+            .map_err(|err| format!("could not read '{}': {}", path.display(), err))?;
+        if let Some(index) = self.files.iter().position(|f| f.canonical == canonical) {
+            return Ok(index);
+        }
+        let source = fs::read_to_string(path)
+            .map_err(|err| format!("could not read '{}': {}", path.display(), err))?;
+        let mut file = MutationFile {
+            path: path.to_path_buf(),
+            source,
+            canonical,
+            includes: vec![],
+        };
+
+        // included paths are owned so the file can be moved in afterwards
+        let included: Vec<(PathBuf, String)> = parse(&file)?
+            .1
+            .statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::Include(include) => Some((
+                    path.parent().unwrap_or(Path::new("")).join(include.path),
+                    position(&file.source, include.path),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        including.push(file.canonical.clone());
+        for (included_path, at) in included {
+            let canonical = fs::canonicalize(&included_path).map_err(|err| {
+                format!(
+                    "'{}': could not read '{}' included at {}, {}",
+                    file.path.display(),
+                    included_path.display(),
+                    at,
+                    err
+                )
+            })?;
+            if including.contains(&canonical) {
+                return Err(format!(
+                    "'{}': including '{}' at {} makes a cycle",
+                    file.path.display(),
+                    included_path.display(),
+                    at
+                )
+                .into());
+            }
+            let index = self.load_file(&included_path, including)?;
+            file.includes.push(index);
+        }
+        including.pop();
+
+        self.files.push(file);
+        Ok(self.files.len() - 1)
+    }
+
+    /// Statements of all files in order. An `INCLUDE` is replaced by the
+    /// statements of the included file, unless it was included before.
+    pub fn grammar(&self) -> Result<XmlMutGrammar<'_>, Box<dyn Error>> {
+        let mut grammars = vec![];
+        for file in &self.files {
+            let (non_parsed, grammar) = parse(file)?;
+            if !non_parsed.is_empty() {
+                eprintln!(
+                    "Parsed xml mut out of {:?}, but there is still remainder left at {}: \n{}",
+                    file.path,
+                    position(&file.source, non_parsed),
+                    non_parsed
+                );
+            }
+            grammars.push(grammar);
+        }
+        let mut added = vec![false; self.files.len()];
+        let mut statements = vec![];
+        for root in &self.roots {
+            self.flatten(*root, &grammars, &mut added, &mut statements);
+        }
+        Ok(XmlMutGrammar { statements })
+    }
+
+    fn flatten<'a>(
+        &self,
+        index: usize,
+        grammars: &[XmlMutGrammar<'a>],
+        added: &mut [bool],
+        statements: &mut Vec<Statement<'a>>,
+    ) {
+        if added[index] {
+            return;
+        }
+        added[index] = true;
+        let mut includes = self.files[index].includes.iter();
+        for statement in &grammars[index].statements {
+            match statement {
+                Statement::Include(_) => {
+                    if let Some(included) = includes.next() {
+                        self.flatten(*included, grammars, added, statements);
+                    }
+                }
+                statement => statements.push(statement.clone()),
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::sarif::write_sarif;
+use clap::ValueEnum;
+use std::{io, path::PathBuf};
+use xml_mut_xot::prelude::Region;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    /// one line per matched node
+    Text,
+    /// an array of objects
+    Json,
+    /// comma separated values with a header row
+    Csv,
+    /// tab separated values with a header row
+    Tsv,
+    /// sarif 2.1.0 log with a result per matched node
+    Sarif,
+}
+
+/// A node matched by a statement together with its selected values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub path: PathBuf,
+    pub element: String,
+    pub region: Region,
+    /// Id of the matching statement
+    pub rule: String,
+    pub values: Vec<(String, Option<String>)>,
+}
+
+/// Selected paths of all rows in order of first appearance. Queries can
+/// select different values, a row leaves the ones it does not have empty.
+fn columns(rows: &[Row]) -> Vec<&str> {
+    let mut columns: Vec<&str> = vec![];
+    for (name, _) in rows.iter().flat_map(|r| r.values.iter()) {
+        if !columns.contains(&name.as_str()) {
+            columns.push(name);
+        }
+    }
+    columns
+}
+
+impl Row {
+    /// Selected values joined by `, `, or the rule of a statement without values
+    pub fn message(&self) -> String {
+        if self.values.is_empty() {
+            return format!("matches {}", self.rule);
+        }
+        let values: Vec<&str> = self
+            .values
+            .iter()
+            .map(|(_, v)| v.as_deref().unwrap_or_default())
+            .collect();
+        values.join(", ")
+    }
+
+    fn value(&self, column: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(name, _)| name == column)
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+impl Output {
+    pub fn write(&self, rows: &[Row], mut writer: impl io::Write) -> io::Result<()> {
+        match self {
+            Output::Text => {
+                for row in rows {
+                    writeln!(
+                        writer,
+                        "{}:{} {} {}",
+                        row.path.display(),
+                        row.region.start_line,
+                        row.element,
+                        row.message()
+                    )?;
+                }
+            }
+            Output::Json => {
+                let rows: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|row| {
+                        let values: serde_json::Map<String, serde_json::Value> = row
+                            .values
+                            .iter()
+                            .map(|(name, value)| (name.clone(), value.clone().into()))
+                            .collect();
+                        serde_json::json!({
+                            "path": row.path.display().to_string(),
+                            "element": row.element,
+                            "line": row.region.start_line,
+                            "values": values,
+                        })
+                    })
+                    .collect();
+                serde_json::to_writer_pretty(&mut writer, &rows)?;
+                writeln!(writer)?;
+            }
+            Output::Csv | Output::Tsv => {
+                let delimiter = if *self == Output::Csv { b',' } else { b'\t' };
+                let mut csv = csv::WriterBuilder::new()
+                    .delimiter(delimiter)
+                    .from_writer(writer);
+                let columns = columns(rows);
+                let mut header = vec!["path", "element", "line"];
+                header.extend(&columns);
+                csv.write_record(&header)?;
+                for row in rows {
+                    let mut record = vec![
+                        row.path.display().to_string(),
+                        row.element.clone(),
+                        row.region.start_line.to_string(),
+                    ];
+                    record.extend(
+                        columns
+                            .iter()
+                            .map(|c| row.value(c).unwrap_or_default().to_string()),
+                    );
+                    csv.write_record(&record)?;
+                }
+                csv.flush()?;
+            }
+            Output::Sarif => write_sarif(rows, writer)?,
+        }
+        Ok(())
+    }
+}
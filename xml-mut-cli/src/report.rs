@@ -0,0 +1,187 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, io, path::PathBuf, time::Duration};
+use xml_mut_xot::prelude::Operation;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// a single json object written after the run
+    Json,
+}
+
+/// An element deleted by `DEDUPLICATE` and where it was in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedDuplicate {
+    pub element: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// What a single statement did in a single file
+#[derive(Debug, Clone, Default)]
+pub struct StatementReport {
+    /// Name of the statement or its position in the mutation file
+    pub label: String,
+    pub nodes_matched: usize,
+    /// Operation count by operation kind
+    pub operations: BTreeMap<&'static str, usize>,
+    pub duplicates: Vec<RemovedDuplicate>,
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+impl StatementReport {
+    pub fn count(&mut self, operations: &[Operation]) {
+        for operation in operations {
+            *self.operations.entry(operation.kind()).or_default() += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub path: PathBuf,
+    /// One per statement in the order of the mutation file
+    pub statements: Vec<StatementReport>,
+    pub written: bool,
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+impl FileReport {
+    pub fn new(path: PathBuf, labels: &[String]) -> Self {
+        FileReport {
+            path,
+            statements: labels
+                .iter()
+                .map(|label| StatementReport {
+                    label: label.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            written: false,
+            error: None,
+            duration: Duration::ZERO,
+        }
+    }
+
+    fn operations(&self) -> BTreeMap<&'static str, usize> {
+        sum_operations(self.statements.iter().map(|s| &s.operations))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub files: Vec<FileReport>,
+    pub duration: Duration,
+}
+
+fn sum_operations<'a>(
+    operations: impl Iterator<Item = &'a BTreeMap<&'static str, usize>>,
+) -> BTreeMap<&'static str, usize> {
+    let mut sum = BTreeMap::new();
+    for (kind, count) in operations.flatten() {
+        *sum.entry(*kind).or_default() += count;
+    }
+    sum
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl Report {
+    pub fn failed_files(&self) -> usize {
+        self.files.iter().filter(|f| f.error.is_some()).count()
+    }
+
+    /// Elements deleted by `DEDUPLICATE` in the files that did not fail
+    pub fn removed_duplicates(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|f| f.error.is_none())
+            .flat_map(|f| &f.statements)
+            .filter_map(|s| s.operations.get("DeleteDuplicate"))
+            .sum()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let statement_count = self.files.first().map_or(0, |f| f.statements.len());
+        let statements: Vec<Value> = (0..statement_count)
+            .map(|i| {
+                let in_files: Vec<&StatementReport> =
+                    self.files.iter().map(|f| &f.statements[i]).collect();
+                json!({
+                    "statement": in_files[0].label,
+                    "files_matched": in_files.iter().filter(|s| s.nodes_matched > 0).count(),
+                    "nodes_matched": in_files.iter().map(|s| s.nodes_matched).sum::<usize>(),
+                    "operations": sum_operations(in_files.iter().map(|s| &s.operations)),
+                    "errors": in_files.iter().filter_map(|s| s.error.as_ref()).count(),
+                    "duration_ms": millis(in_files.iter().map(|s| s.duration).sum()),
+                })
+            })
+            .collect();
+
+        let files: Vec<Value> = self
+            .files
+            .iter()
+            .map(|file| {
+                let statements: Vec<Value> = file
+                    .statements
+                    .iter()
+                    .map(|s| {
+                        let duplicates: Vec<Value> = s
+                            .duplicates
+                            .iter()
+                            .map(|d| {
+                                json!({
+                                    "element": d.element,
+                                    "line": d.line,
+                                    "column": d.column,
+                                })
+                            })
+                            .collect();
+                        json!({
+                            "statement": s.label,
+                            "nodes_matched": s.nodes_matched,
+                            "operations": s.operations,
+                            "duplicates_removed": duplicates,
+                            "error": s.error,
+                            "duration_ms": millis(s.duration),
+                        })
+                    })
+                    .collect();
+                json!({
+                    "path": file.path.display().to_string(),
+                    "operations": file.operations(),
+                    "written": file.written,
+                    "error": file.error,
+                    "duration_ms": millis(file.duration),
+                    "statements": statements,
+                })
+            })
+            .collect();
+
+        json!({
+            "files": files,
+            "statements": statements,
+            "totals": {
+                "files": self.files.len(),
+                "files_written": self.files.iter().filter(|f| f.written).count(),
+                "files_failed": self.failed_files(),
+                "duplicates_removed": self.removed_duplicates(),
+                "operations": sum_operations(self.files.iter().flat_map(|f| f.statements.iter().map(|s| &s.operations))),
+                "duration_ms": millis(self.duration),
+            },
+        })
+    }
+
+    pub fn write(&self, format: ReportFormat, mut writer: impl io::Write) -> io::Result<()> {
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, &self.to_json())?;
+                writeln!(writer)
+            }
+        }
+    }
+}
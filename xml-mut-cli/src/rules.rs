@@ -0,0 +1,118 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+use xml_mut_data::Statement;
+use xml_mut_parse::prelude::xml_mut_grammar;
+
+/// Extension of rule files, `@bump-json` is found as `bump-json.xmlmut`
+const RULE_EXTENSION: &str = "xmlmut";
+
+/// A named rule file found on the search path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub path: PathBuf,
+    /// First comment of the file
+    pub description: String,
+}
+
+/// Directories searched for named rule files, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleLibrary {
+    pub dirs: Vec<PathBuf>,
+}
+
+impl RuleLibrary {
+    /// Directories of `$XML_MUT_PATH`, then `.xml-mut` of the current
+    /// directory or the nearest parent having one, then `~/.xml-mut`
+    pub fn from_env() -> Self {
+        let mut dirs: Vec<PathBuf> = env::var_os("XML_MUT_PATH")
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_default();
+        if let Some(local) = env::current_dir().ok().and_then(|cwd| {
+            cwd.ancestors()
+                .map(|dir| dir.join(".xml-mut"))
+                .find(|dir| dir.is_dir())
+        }) {
+            dirs.push(local);
+        }
+        if let Some(home) = env::home_dir() {
+            dirs.push(home.join(".xml-mut"));
+        }
+        dirs.retain(|dir| !dir.as_os_str().is_empty());
+        RuleLibrary { dirs }
+    }
+
+    /// Path of the rule file named `name`, the first one on the search path
+    pub fn find(&self, name: &str) -> Result<PathBuf, String> {
+        self.dirs
+            .iter()
+            .map(|dir| dir.join(format!("{}.{}", name, RULE_EXTENSION)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| {
+                let searched: Vec<String> =
+                    self.dirs.iter().map(|d| d.display().to_string()).collect();
+                format!(
+                    "There is no rule named '@{}', searched: {}.",
+                    name,
+                    searched.join(", ")
+                )
+            })
+    }
+
+    /// Mutation file paths with `@name` arguments replaced by rule file paths
+    pub fn expand(&self, paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+        paths
+            .iter()
+            .map(
+                |path| match path.to_str().and_then(|p| p.strip_prefix('@')) {
+                    Some(name) => self.find(name),
+                    None => Ok(path.clone()),
+                },
+            )
+            .collect()
+    }
+
+    /// Rules available on the search path sorted by name, a rule hidden by
+    /// one of the same name in an earlier directory is left out
+    pub fn list(&self) -> io::Result<Vec<Rule>> {
+        let mut rules: Vec<Rule> = vec![];
+        for dir in self.dirs.iter().filter(|d| d.is_dir()) {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+                if !path.is_file() || path.extension().is_none_or(|e| e != RULE_EXTENSION) {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if rules.iter().any(|r| r.name == name) {
+                    continue;
+                }
+                rules.push(Rule {
+                    name: name.to_string(),
+                    description: description(&path),
+                    path,
+                });
+            }
+        }
+        rules.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(rules)
+    }
+}
+
+/// First comment of a rule file with its whitespace collapsed,
+/// empty when there is none or the file does not parse
+fn description(path: &Path) -> String {
+    let source = fs::read_to_string(path).unwrap_or_default();
+    let comment = xml_mut_grammar(&source).ok().and_then(|(_, grammar)| {
+        grammar.statements.into_iter().find_map(|s| match s {
+            Statement::Comment(comment) => Some(comment),
+            _ => None,
+        })
+    });
+    comment
+        .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
+}
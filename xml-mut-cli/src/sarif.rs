@@ -0,0 +1,63 @@
+use crate::output::Row;
+use serde_json::{json, Value};
+use std::io;
+
+/// Writes rows as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+/// log, each row is a result of the rule of its statement.
+pub fn write_sarif(rows: &[Row], mut writer: impl io::Write) -> io::Result<()> {
+    let mut rules: Vec<&str> = vec![];
+    for row in rows {
+        if !rules.contains(&row.rule.as_str()) {
+            rules.push(&row.rule);
+        }
+    }
+
+    let results: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            let rule_index = rules.iter().position(|r| *r == row.rule);
+            json!({
+                "ruleId": row.rule,
+                "ruleIndex": rule_index,
+                "level": "warning",
+                "message": {
+                    "text": format!("{}: {}", row.element, row.message()),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            // uris use forward slashes on every platform
+                            "uri": row.path.display().to_string().replace('\\', "/"),
+                        },
+                        "region": {
+                            "startLine": row.region.start_line,
+                            "startColumn": row.region.start_column,
+                            "endLine": row.region.end_line,
+                            "endColumn": row.region.end_column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+
+    let rules: Vec<Value> = rules.iter().map(|id| json!({ "id": id })).collect();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "xml-mut",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/tomuxmon/xml-mut",
+                    "rules": rules,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut writer, &log)?;
+    writeln!(writer)
+}
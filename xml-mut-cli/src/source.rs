@@ -0,0 +1,196 @@
+use std::{
+    io::{Error, ErrorKind},
+    ops::Range,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Newline {
+    Lf,
+    CrLf,
+    /// Both styles, every line ending is written as it is
+    Mixed,
+}
+
+/// Decoded xml text together with everything needed to write it back
+/// the way it was read: encoding, byte order mark, newline style and the
+/// xml declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlSource {
+    /// decoded xml text without the byte order mark
+    pub text: String,
+    pub encoding: Encoding,
+    pub bom: bool,
+    pub newline: Newline,
+    pub declaration: Option<String>,
+}
+
+const BOM_UTF8: &[u8] = &[0xEF, 0xBB, 0xBF];
+const BOM_UTF16_LE: &[u8] = &[0xFF, 0xFE];
+const BOM_UTF16_BE: &[u8] = &[0xFE, 0xFF];
+
+fn invalid_data(err: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::new(ErrorKind::InvalidData, err)
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, Error> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "utf-16 text has an odd number of bytes",
+        ));
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| from_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(invalid_data)
+}
+
+/// The `<?xml ... ?>` declaration the text starts with, if any.
+fn declaration_of(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix("<?xml")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = text.find("?>")?;
+    Some(&text[..end + 2])
+}
+
+/// Range of the value of a pseudo-attribute like `encoding` in the
+/// declaration, without the quotes. There can be whitespace around `=`.
+fn pseudo_attribute(declaration: &str, name: &str) -> Option<Range<usize>> {
+    let mut from = 0;
+    while let Some(at) = declaration[from..].find(name).map(|i| from + i) {
+        from = at + name.len();
+        if !declaration[..at].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(rest) = declaration[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            continue;
+        };
+        let start = declaration.len() - rest.len() + quote.len_utf8();
+        let end = start + declaration[start..].find(quote)?;
+        return Some(start..end);
+    }
+    None
+}
+
+impl XmlSource {
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let (encoding, bom, text) = if let Some(rest) = bytes.strip_prefix(BOM_UTF8) {
+            (Encoding::Utf8, true, rest)
+        } else if let Some(rest) = bytes.strip_prefix(BOM_UTF16_LE) {
+            (Encoding::Utf16Le, true, rest)
+        } else if let Some(rest) = bytes.strip_prefix(BOM_UTF16_BE) {
+            (Encoding::Utf16Be, true, rest)
+        } else if bytes.starts_with(&[b'<', 0]) {
+            (Encoding::Utf16Le, false, bytes)
+        } else if bytes.starts_with(&[0, b'<']) {
+            (Encoding::Utf16Be, false, bytes)
+        } else {
+            (Encoding::Utf8, false, bytes)
+        };
+
+        let text = match encoding {
+            Encoding::Utf8 => String::from_utf8(text.to_vec()).map_err(invalid_data)?,
+            Encoding::Utf16Le => decode_utf16(text, u16::from_le_bytes)?,
+            Encoding::Utf16Be => decode_utf16(text, u16::from_be_bytes)?,
+        };
+
+        let crlf = text.matches("\r\n").count();
+        let newline = match text.matches('\n').count() {
+            lf if crlf == 0 || lf == 0 => Newline::Lf,
+            lf if lf == crlf => Newline::CrLf,
+            _ => Newline::Mixed,
+        };
+        let declaration = declaration_of(&text).map(|d| d.to_string());
+
+        Ok(XmlSource {
+            text,
+            encoding,
+            bom,
+            newline,
+            declaration,
+        })
+    }
+
+    /// Same source written as utf-8 without a byte order mark and with `\n`
+    /// line endings. The declaration encoding is changed to utf-8 as well.
+    pub fn normalized(&self) -> Self {
+        let declaration =
+            self.declaration
+                .as_ref()
+                .map(|d| match pseudo_attribute(d, "encoding") {
+                    Some(value) => format!("{}utf-8{}", &d[..value.start], &d[value.end..]),
+                    None => d.clone(),
+                });
+        XmlSource {
+            text: self.text.clone(),
+            encoding: Encoding::Utf8,
+            bom: false,
+            newline: Newline::Lf,
+            declaration,
+        }
+    }
+
+    /// Encodes mutated xml text back the way the source was read,
+    /// restoring the declaration if serialization dropped it.
+    pub fn encode(&self, xml: &str) -> Vec<u8> {
+        let mut text = String::with_capacity(xml.len());
+        let body = match declaration_of(xml) {
+            Some(declaration) => &xml[declaration.len()..],
+            None => {
+                if self.declaration.is_some() {
+                    text.push('\n');
+                }
+                xml
+            }
+        };
+        if let Some(declaration) = &self.declaration {
+            text.insert_str(0, declaration);
+        }
+        text.push_str(body);
+
+        // line endings of a source with a single style are the ones of
+        // inserted or serialized text too, a mixed source is kept as it is
+        text = match self.newline {
+            Newline::Lf => text.replace("\r\n", "\n"),
+            Newline::CrLf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+            Newline::Mixed => text,
+        };
+
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        match self.encoding {
+            Encoding::Utf8 => {
+                if self.bom {
+                    bytes.extend_from_slice(BOM_UTF8);
+                }
+                bytes.extend_from_slice(text.as_bytes());
+            }
+            Encoding::Utf16Le => {
+                if self.bom {
+                    bytes.extend_from_slice(BOM_UTF16_LE);
+                }
+                bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+            Encoding::Utf16Be => {
+                if self.bom {
+                    bytes.extend_from_slice(BOM_UTF16_BE);
+                }
+                bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            }
+        }
+        bytes
+    }
+}
@@ -0,0 +1,26 @@
+use std::{fs, io::Write, path::Path};
+use tempfile::NamedTempFile;
+
+/// Writes `output` over the file at `path` unless it equals `original`.
+/// Content goes to a temp file in the same directory first and is then
+/// renamed over the original, so a failure never leaves a truncated file.
+/// Returns `true` if the file was written.
+pub fn write_if_changed(path: &Path, original: &[u8], output: &[u8]) -> std::io::Result<bool> {
+    if original == output {
+        return Ok(false);
+    }
+
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let permissions = fs::metadata(path)?.permissions();
+
+    let mut temp_file = NamedTempFile::new_in(dir)?;
+    temp_file.write_all(output)?;
+    temp_file.as_file().sync_all()?;
+    temp_file.as_file().set_permissions(permissions)?;
+    temp_file.persist(path).map_err(|e| e.error)?;
+
+    Ok(true)
+}
@@ -0,0 +1,84 @@
+use std::{fs, process::Command};
+
+const XML: &str = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="System.Text.Json" Version="7.0.2" />
+    <PackageReference Include="Mono.Cecil">
+      <Version>0.11.4</Version>
+    </PackageReference>
+  </ItemGroup>
+</Project>
+"#;
+
+#[test]
+fn check_writes_sarif_results_without_writing() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.csproj");
+    let xml_mut_path = dir.path().join("lint.xmlmut");
+    fs::write(&xml_path, XML).unwrap();
+    fs::write(
+        &xml_mut_path,
+        "GET Project/ItemGroup/PackageReference\nSET [@Version] = Version[text]\nDELETE Version",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(["--check", "--output", "sarif", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    assert!(!output.status.success(), "findings should fail the check");
+    assert_eq!(fs::read_to_string(&xml_path).unwrap(), XML);
+
+    let log: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("output should be json");
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "xml-mut");
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        serde_json::json!([{ "id": "statement-1" }])
+    );
+
+    let results = run["results"]
+        .as_array()
+        .expect("results should be an array");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "statement-1");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert!(location["artifactLocation"]["uri"]
+        .as_str()
+        .unwrap()
+        .ends_with("in.csproj"));
+    assert_eq!(
+        location["region"],
+        serde_json::json!({
+            "startLine": 4,
+            "startColumn": 5,
+            "endLine": 6,
+            "endColumn": 24
+        })
+    );
+}
+
+#[test]
+fn check_passes_without_findings() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.csproj");
+    let xml_mut_path = dir.path().join("lint.xmlmut");
+    fs::write(&xml_path, XML).unwrap();
+    fs::write(&xml_mut_path, "GET Project/Nope\nDELETE Version").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(["--check", "--output", "sarif", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    assert!(output.status.success());
+
+    let log: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("output should be json");
+    assert_eq!(log["runs"][0]["results"], serde_json::json!([]));
+}
@@ -0,0 +1,114 @@
+use std::{fs, path::Path, process::Command};
+
+const XML: &str = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="Mono.Cecil" Version="0.11.4" />
+  </ItemGroup>
+</Project>"#;
+
+fn run(dir: &Path, xml_mut_paths: &[&str]) -> (bool, String) {
+    let xml_path = dir.join("in.csproj");
+    fs::write(&xml_path, XML).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .current_dir(dir)
+        .args(xml_mut_paths)
+        .args(["--format", "lossless", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    (
+        output.status.success(),
+        String::from_utf8(output.stderr).expect("output should be utf-8"),
+    )
+}
+
+fn write(dir: &Path, path: &str, contents: &str) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn included_statements_run_in_place() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    write(
+        dir.path(),
+        "main.xmlmut",
+        "INCLUDE \"common/dotnet.xmlmut\"\nGET Project/ItemGroup/PackageReference\nSET [@Version] = \"0.11.6\"",
+    );
+    // relative to the including file, included once
+    write(
+        dir.path(),
+        "common/dotnet.xmlmut",
+        "INCLUDE \"versions.xmlmut\"\nINCLUDE \"versions.xmlmut\"",
+    );
+    write(
+        dir.path(),
+        "common/versions.xmlmut",
+        "GET Project/ItemGroup/PackageReference\nSET [@Version] = \"0.11.5\"\n\nGET Project/ItemGroup/PackageReference\nSET [@Pinned] = \"true\"",
+    );
+    write(
+        dir.path(),
+        "label.xmlmut",
+        "GET Project/ItemGroup\nSET [@Label] = \"packages\"",
+    );
+
+    let (success, stderr) = run(dir.path(), &["main.xmlmut", "label.xmlmut"]);
+    assert!(success, "{}", stderr);
+    assert!(stderr.contains("4 mutation(s) to be applied."));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        r#"<Project>
+  <ItemGroup Label="packages">
+    <PackageReference Include="Mono.Cecil" Version="0.11.6" Pinned="true" />
+  </ItemGroup>
+</Project>"#
+    );
+}
+
+#[test]
+fn include_cycles_are_rejected() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    write(
+        dir.path(),
+        "main.xmlmut",
+        "INCLUDE \"common/dotnet.xmlmut\"",
+    );
+    write(
+        dir.path(),
+        "common/dotnet.xmlmut",
+        "INCLUDE \"../main.xmlmut\"",
+    );
+
+    let (success, stderr) = run(dir.path(), &["main.xmlmut"]);
+    assert!(!success);
+    assert!(stderr.contains(
+        "'common/dotnet.xmlmut': including 'common/../main.xmlmut' at line 1, column 10 makes a cycle"
+    ));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML
+    );
+}
+
+#[test]
+fn diagnostics_point_into_included_file() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    write(
+        dir.path(),
+        "main.xmlmut",
+        "INCLUDE \"common/dotnet.xmlmut\"",
+    );
+    write(
+        dir.path(),
+        "common/dotnet.xmlmut",
+        "GET Project/ItemGroup/PackageReference\nSET [@Version] = $version",
+    );
+
+    let (success, stderr) = run(dir.path(), &["main.xmlmut"]);
+    assert!(!success);
+    assert!(stderr.contains(
+        "\"common/dotnet.xmlmut\": Parameter $version is not bound at line 2, column 18"
+    ));
+}
@@ -0,0 +1,83 @@
+use std::{fs, path::Path, process::Command};
+
+const XML: &str = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="Mono.Cecil" Version="0.11.4" />
+  </ItemGroup>
+</Project>"#;
+
+const XML_MUT: &str = r#"MUTATION bump_cecil: GET Project/ItemGroup/PackageReference
+WHERE [@Include] == "Mono.Cecil"
+SET [@Version] = "0.11.5"
+
+/* @name private_assets */
+GET Project/ItemGroup/PackageReference
+SET [@PrivateAssets] = "all""#;
+
+fn run(dir: &Path, args: &[&str]) -> (bool, String) {
+    let xml_path = dir.join("in.csproj");
+    let xml_mut_path = dir.join("mut.xmlmut");
+    fs::write(&xml_path, XML).unwrap();
+    fs::write(&xml_mut_path, XML_MUT).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(args)
+        .args(["--format", "lossless", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).expect("output should be utf-8"),
+    )
+}
+
+#[test]
+fn only_runs_named_statement() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, _) = run(dir.path(), &["--only", "bump_cecil"]);
+    assert!(success);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML.replace("0.11.4", "0.11.5")
+    );
+}
+
+#[test]
+fn skip_leaves_named_statement_out() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, stdout) = run(dir.path(), &["--skip", "bump_cecil", "--report", "json"]);
+    assert!(success);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML.replace(
+            "Version=\"0.11.4\"",
+            "Version=\"0.11.4\" PrivateAssets=\"all\""
+        )
+    );
+
+    let report: serde_json::Value = serde_json::from_str(&stdout).expect("report should be json");
+    assert_eq!(
+        report["statements"],
+        serde_json::json!([{
+            "statement": "private_assets",
+            "files_matched": 1,
+            "nodes_matched": 1,
+            "operations": { "SetAttribute": 1 },
+            "errors": 0,
+            "duration_ms": report["statements"][0]["duration_ms"]
+        }])
+    );
+}
+
+#[test]
+fn unknown_name_is_rejected() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, _) = run(dir.path(), &["--only", "nope"]);
+    assert!(!success);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML
+    );
+}
@@ -0,0 +1,86 @@
+use std::{fs, path::Path, process::Command};
+
+const XML: &str = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="Mono.Cecil" Version="0.11.4" />
+    <PackageReference Include="System.Text.Json" Version="7.0.2" />
+  </ItemGroup>
+</Project>"#;
+
+const XML_MUT: &str = r#"GET Project/ItemGroup/PackageReference
+WHERE [@Include] == $package
+SET [@Version] = $version"#;
+
+fn run(dir: &Path, args: &[&str]) -> (bool, String) {
+    run_with(dir, XML_MUT, args)
+}
+
+fn run_with(dir: &Path, xml_mut: &str, args: &[&str]) -> (bool, String) {
+    let xml_path = dir.join("in.csproj");
+    let xml_mut_path = dir.join("mut.xmlmut");
+    fs::write(&xml_path, XML).unwrap();
+    fs::write(&xml_mut_path, xml_mut).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .args(args)
+        .arg(&xml_mut_path)
+        .args(["--format", "lossless", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    (
+        output.status.success(),
+        String::from_utf8(output.stderr).expect("output should be utf-8"),
+    )
+}
+
+#[test]
+fn defined_parameters_are_bound() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, _) = run(
+        dir.path(),
+        &["--define", "package=Mono.Cecil", "-D", "version=0.11.6"],
+    );
+    assert!(success);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML.replace("0.11.4", "0.11.6")
+    );
+}
+
+#[test]
+fn unbound_parameters_are_rejected_with_location() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, stderr) = run(dir.path(), &["--define", "package=Mono.Cecil"]);
+    assert!(!success);
+    assert!(stderr.contains("Parameter $version is not bound at line 3, column 18"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML
+    );
+}
+
+#[test]
+fn let_values_are_overridden_by_defines() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_mut = format!("LET $package = \"Mono.Cecil\"\nLET $version = \"0.11.5\"\n{XML_MUT}");
+    let (success, _) = run_with(dir.path(), &xml_mut, &["-D", "version=0.11.6"]);
+    assert!(success);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.csproj")).unwrap(),
+        XML.replace("0.11.4", "0.11.6")
+    );
+}
+
+#[test]
+fn predicate_cycles_are_rejected() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_mut = r#"DEFINE PREDICATE a AS exists Version and b
+DEFINE PREDICATE b AS a
+GET Project/ItemGroup/PackageReference
+WHERE a
+DELETE [@Version]"#;
+    let (success, stderr) = run_with(dir.path(), xml_mut, &[]);
+    assert!(!success);
+    assert!(stderr.contains("is defined in terms of itself"));
+}
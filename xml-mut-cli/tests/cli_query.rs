@@ -0,0 +1,143 @@
+use std::{fs, path::Path, process::Command};
+
+const XML: &str = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="System.Text.Json" Version="7.0.2" />
+    <PackageReference Include="Mono.Cecil">
+      <Version>0.11.4</Version>
+    </PackageReference>
+  </ItemGroup>
+</Project>
+"#;
+
+const QUERY: &str =
+    "GET Project/ItemGroup/PackageReference\nSELECT [@Include], [@Version], Version[text]";
+
+fn run_query(dir: &Path, args: &[&str]) -> String {
+    run_select(dir, QUERY, args)
+}
+
+fn run_select(dir: &Path, query: &str, args: &[&str]) -> String {
+    let xml_path = dir.join("in.csproj");
+    let xml_mut_path = dir.join("query.xmlmut");
+    fs::write(&xml_path, XML).unwrap();
+    fs::write(&xml_mut_path, query).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(args)
+        .args(["include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).expect("output should be utf-8")
+}
+
+#[test]
+fn query_prints_selected_values_without_writing() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let stdout = run_query(dir.path(), &["--format", "pretty"]);
+
+    let path = dir.path().join("in.csproj");
+    assert_eq!(
+        stdout,
+        format!(
+            "{0}:3 Project/ItemGroup/PackageReference System.Text.Json, 7.0.2, \n\
+             {0}:4 Project/ItemGroup/PackageReference Mono.Cecil, , 0.11.4\n",
+            path.display()
+        )
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), XML);
+}
+
+#[test]
+fn query_output_json() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let stdout = run_query(dir.path(), &["--output", "json"]);
+
+    let rows: serde_json::Value = serde_json::from_str(&stdout).expect("output should be json");
+    let path = dir.path().join("in.csproj").display().to_string();
+    assert_eq!(
+        rows,
+        serde_json::json!([
+            {
+                "path": path,
+                "element": "Project/ItemGroup/PackageReference",
+                "line": 3,
+                "values": {
+                    "[@Include]": "System.Text.Json",
+                    "[@Version]": "7.0.2",
+                    "Version[text]": null
+                }
+            },
+            {
+                "path": path,
+                "element": "Project/ItemGroup/PackageReference",
+                "line": 4,
+                "values": {
+                    "[@Include]": "Mono.Cecil",
+                    "[@Version]": null,
+                    "Version[text]": "0.11.4"
+                }
+            }
+        ])
+    );
+}
+
+#[test]
+fn query_output_json_keeps_select_order() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let query = "GET Project/ItemGroup/PackageReference\nSELECT [@Version], [@Include]";
+    let stdout = run_select(dir.path(), query, &["--output", "json"]);
+
+    let rows: serde_json::Value = serde_json::from_str(&stdout).expect("output should be json");
+    let keys: Vec<&String> = rows[0]["values"].as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["[@Version]", "[@Include]"]);
+    let keys: Vec<&String> = rows[0].as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["path", "element", "line", "values"]);
+}
+
+#[test]
+fn query_output_csv_and_tsv() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let path = dir.path().join("in.csproj");
+
+    let csv = run_query(dir.path(), &["--output", "csv"]);
+    assert_eq!(
+        csv,
+        format!(
+            "path,element,line,[@Include],[@Version],Version[text]\n\
+             {0},Project/ItemGroup/PackageReference,3,System.Text.Json,7.0.2,\n\
+             {0},Project/ItemGroup/PackageReference,4,Mono.Cecil,,0.11.4\n",
+            path.display()
+        )
+    );
+
+    let tsv = run_query(dir.path(), &["--output", "tsv"]);
+    assert_eq!(
+        tsv.lines().next(),
+        Some("path\telement\tline\t[@Include]\t[@Version]\tVersion[text]")
+    );
+    assert_eq!(tsv.lines().count(), 3);
+}
+
+#[test]
+fn query_skips_nodes_deleted_by_earlier_statements() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let stdout = run_select(
+        dir.path(),
+        "GET Project/ItemGroup\nDELETE PackageReference\n\n\
+         GET Project/ItemGroup/PackageReference\nSELECT [@Include]",
+        &[],
+    );
+
+    let path = dir.path().join("in.csproj");
+    assert_eq!(
+        stdout,
+        format!(
+            "{}:4 Project/ItemGroup/PackageReference Mono.Cecil\n",
+            path.display()
+        )
+    );
+}
@@ -0,0 +1,187 @@
+use std::{fs, path::Path, process::Command};
+
+const MUTATED: &str = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="Mono.Cecil">
+      <Version>0.11.4</Version>
+    </PackageReference>
+  </ItemGroup>
+</Project>"#;
+
+const UNTOUCHED: &str = r#"<Project>
+  <ItemGroup>
+    <Compile Include="Program.cs" />
+  </ItemGroup>
+</Project>"#;
+
+fn run_with_report(xml_mut: &str, dir: &Path) -> (bool, serde_json::Value) {
+    let xml_mut_path = dir.join("mut.xmlmut");
+    fs::write(&xml_mut_path, xml_mut).unwrap();
+    fs::write(dir.join("a.csproj"), MUTATED).unwrap();
+    fs::write(dir.join("b.csproj"), UNTOUCHED).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(["--report", "json", "include", "-x"])
+        .arg(dir.join("a.csproj"))
+        .arg("-x")
+        .arg(dir.join("b.csproj"))
+        .output()
+        .expect("xml-mut should run");
+    let report = serde_json::from_slice(&output.stdout).expect("report should be json");
+    (output.status.success(), report)
+}
+
+#[test]
+fn report_counts_operations_per_file_and_statement() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, report) = run_with_report(
+        "GET Project/ItemGroup/PackageReference\nSET [@Version] = Version[text]\nDELETE Version\n\
+         GET Project/ItemGroup/Compile\nSET [@Visible] = \"false\"",
+        dir.path(),
+    );
+    assert!(success);
+
+    let files = report["files"]
+        .as_array()
+        .expect("files should be an array");
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["written"], true);
+    assert_eq!(files[0]["error"], serde_json::Value::Null);
+    assert_eq!(
+        files[0]["operations"],
+        serde_json::json!({ "SetAttribute": 1, "DeleteNode": 1 })
+    );
+    assert_eq!(files[0]["statements"][0]["nodes_matched"], 1);
+    assert_eq!(files[0]["statements"][1]["nodes_matched"], 0);
+    assert_eq!(files[1]["written"], true);
+    assert_eq!(
+        files[1]["operations"],
+        serde_json::json!({ "SetAttribute": 1 })
+    );
+
+    let statements = report["statements"]
+        .as_array()
+        .expect("statements should be an array");
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[0]["files_matched"], 1);
+    assert_eq!(statements[1]["files_matched"], 1);
+    assert_eq!(report["totals"]["files_written"], 2);
+    assert_eq!(
+        report["totals"]["operations"],
+        serde_json::json!({ "SetAttribute": 2, "DeleteNode": 1 })
+    );
+    assert!(report["totals"]["duration_ms"].is_f64());
+}
+
+#[test]
+fn report_records_errors_and_keeps_going() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let (success, report) = run_with_report(
+        "GET Project/ItemGroup/PackageReference\nDELETE Nope\n\
+         GET Project/ItemGroup/Compile\nSET [@Visible] = \"false\"",
+        dir.path(),
+    );
+    assert!(!success);
+
+    let files = report["files"]
+        .as_array()
+        .expect("files should be an array");
+    assert_eq!(files[0]["written"], false);
+    assert!(files[0]["error"].is_string());
+    assert!(files[0]["statements"][0]["error"].is_string());
+    assert_eq!(report["statements"][0]["errors"], 1);
+    assert_eq!(files[1]["written"], true);
+    assert_eq!(report["totals"]["files_failed"], 1);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("a.csproj")).unwrap(),
+        MUTATED
+    );
+}
+
+#[test]
+fn report_counts_removed_duplicates() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    let xml_path = dir.path().join("a.csproj");
+    fs::write(
+        &xml_mut_path,
+        "GET Project/ItemGroup\nDEDUPLICATE Compile BY [@Include]",
+    )
+    .unwrap();
+    fs::write(
+        &xml_path,
+        "<Project>\n  <ItemGroup>\n    <Compile Include=\"a.cs\" />\n    \
+         <Compile Include=\"a.cs\" />\n    <Compile Include=\"a.cs\" />\n  </ItemGroup>\n</Project>",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(["--report", "json", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    assert!(output.status.success());
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("report should be json");
+    assert_eq!(report["totals"]["duplicates_removed"], 2);
+    assert_eq!(
+        report["totals"]["operations"],
+        serde_json::json!({ "DeleteDuplicate": 2 })
+    );
+    assert_eq!(
+        report["files"][0]["statements"][0]["duplicates_removed"],
+        serde_json::json!([
+            { "element": "Project/ItemGroup/Compile", "line": 4, "column": 5 },
+            { "element": "Project/ItemGroup/Compile", "line": 5, "column": 5 },
+        ])
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("a.csproj:4:5 - duplicate Project/ItemGroup/Compile removed"));
+    assert!(stderr.contains("2 duplicate(s) removed."));
+    assert_eq!(
+        fs::read_to_string(&xml_path).unwrap(),
+        "<Project>\n  <ItemGroup>\n    <Compile Include=\"a.cs\"/>\n  </ItemGroup>\n</Project>"
+    );
+}
+
+#[test]
+fn report_and_rows_on_stdout_are_rejected() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    let xml_path = dir.path().join("a.csproj");
+    fs::write(
+        &xml_mut_path,
+        "GET Project/ItemGroup/PackageReference\nSELECT [@Include]",
+    )
+    .unwrap();
+    fs::write(&xml_path, MUTATED).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(["--report", "json", "include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--report-file"));
+
+    let report_path = dir.path().join("report.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(&xml_mut_path)
+        .args(["--output", "json", "--report", "json", "--report-file"])
+        .arg(&report_path)
+        .args(["include", "-x"])
+        .arg(&xml_path)
+        .output()
+        .expect("xml-mut should run");
+    assert!(output.status.success());
+    let rows: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("rows should be json");
+    assert_eq!(rows[0]["values"]["[@Include]"], "Mono.Cecil");
+    let report: serde_json::Value =
+        serde_json::from_slice(&fs::read(&report_path).unwrap()).expect("report should be json");
+    assert_eq!(report["totals"]["files"], 1);
+}
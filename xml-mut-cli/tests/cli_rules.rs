@@ -0,0 +1,96 @@
+use std::{fs, path::Path, process::Command};
+
+/// Rule library with a home, a project and an `$XML_MUT_PATH` directory
+fn library(root: &Path) {
+    let write = |path: &str, contents: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    };
+    write(
+        "env/bump-json.xmlmut",
+        "/* Bumps System.Text.Json\n   to 8.0.0 */\nGET Project\nSET [@From] = \"env\"",
+    );
+    write(
+        "home/.xml-mut/bump-json.xmlmut",
+        "/* home bump */\nGET Project\nSET [@From] = \"home\"",
+    );
+    write(
+        "home/.xml-mut/pin.xmlmut",
+        "GET Project\nSET [@Pinned] = \"true\"",
+    );
+    write(
+        "project/.xml-mut/bump-json.xmlmut",
+        "/* project bump */\nGET Project\nSET [@From] = \"project\"",
+    );
+    write("project/src/in.csproj", "<Project/>");
+}
+
+fn xml_mut(root: &Path, xml_mut_path: Option<&str>) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_xml-mut"));
+    command
+        .current_dir(root.join("project/src"))
+        .env("HOME", root.join("home"))
+        .env_remove("XML_MUT_PATH");
+    if let Some(xml_mut_path) = xml_mut_path {
+        command.env("XML_MUT_PATH", root.join(xml_mut_path));
+    }
+    command
+}
+
+fn run_rules(root: &Path, xml_mut_path: Option<&str>, rules: &[&str]) -> String {
+    let xml_path = root.join("project/src/in.csproj");
+    let status = xml_mut(root, xml_mut_path)
+        .args(rules)
+        .args(["include", "-x"])
+        .arg(&xml_path)
+        .status()
+        .expect("xml-mut should run");
+    assert!(status.success());
+    fs::read_to_string(&xml_path).unwrap()
+}
+
+#[test]
+fn named_rules_follow_search_path() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    library(dir.path());
+
+    assert_eq!(
+        run_rules(dir.path(), Some("env"), &["@bump-json"]),
+        r#"<Project From="env"/>"#
+    );
+    assert_eq!(
+        run_rules(dir.path(), None, &["@bump-json", "@pin"]),
+        r#"<Project From="project" Pinned="true"/>"#
+    );
+}
+
+#[test]
+fn unknown_rule_is_an_error() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    library(dir.path());
+
+    let output = xml_mut(dir.path(), None)
+        .args(["@nope", "include", "-x", "in.csproj"])
+        .output()
+        .expect("xml-mut should run");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("There is no rule named '@nope'"));
+}
+
+#[test]
+fn rules_are_listed_with_descriptions() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    library(dir.path());
+
+    let output = xml_mut(dir.path(), Some("env"))
+        .args(["rules", "list"])
+        .output()
+        .expect("xml-mut should run");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "@bump-json  Bumps System.Text.Json to 8.0.0\n@pin\n"
+    );
+}
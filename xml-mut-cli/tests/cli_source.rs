@@ -0,0 +1,145 @@
+use std::{fs, path::Path, process::Command};
+
+fn run_include(xml_mut_path: &Path, xml_path: &Path, args: &[&str]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(xml_mut_path)
+        .args(args)
+        .arg("include")
+        .arg("-x")
+        .arg(xml_path)
+        .status()
+        .expect("xml-mut should run");
+    assert!(status.success());
+}
+
+fn utf16le_with_bom(text: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    bytes
+}
+
+#[test]
+fn bom_crlf_and_declaration_are_kept() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    fs::copy("tests/bom_crlf_declaration/in.xml", &xml_path).expect("copy should not fail");
+
+    run_include(
+        Path::new("tests/bom_crlf_declaration/mut.xmlmut"),
+        &xml_path,
+        &[],
+    );
+
+    let xml_expected = fs::read("tests/bom_crlf_declaration/out.xml").expect("should exist");
+    let xml_new = fs::read(&xml_path).expect("xml should exist");
+    assert_eq!(xml_expected, xml_new);
+}
+
+#[test]
+fn utf16_is_kept() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    fs::write(
+        &xml_path,
+        utf16le_with_bom("<?xml version=\"1.0\" encoding=\"utf-16\"?>\r\n<a>\r\n  <b/>\r\n</a>"),
+    )
+    .unwrap();
+    fs::write(&xml_mut_path, "GET a/b\nSET [@c] = \"ž\"").unwrap();
+
+    run_include(&xml_mut_path, &xml_path, &["--format", "lossless"]);
+
+    let xml_new = fs::read(&xml_path).expect("xml should exist");
+    assert_eq!(
+        xml_new,
+        utf16le_with_bom(
+            "<?xml version=\"1.0\" encoding=\"utf-16\"?>\r\n<a>\r\n  <b c=\"ž\"/>\r\n</a>"
+        )
+    );
+}
+
+#[test]
+fn normalize_writes_utf8_with_lf() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    fs::write(
+        &xml_path,
+        utf16le_with_bom("<?xml version=\"1.0\" encoding=\"utf-16\"?>\r\n<a>\r\n  <b/>\r\n</a>"),
+    )
+    .unwrap();
+    fs::write(&xml_mut_path, "GET a/b\nSET [@c] = \"d\"").unwrap();
+
+    run_include(&xml_mut_path, &xml_path, &["--normalize", "--format", "lossless"]);
+
+    let xml_new = fs::read_to_string(&xml_path).expect("xml should be utf-8");
+    assert_eq!(
+        xml_new,
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<a>\n  <b c=\"d\"/>\n</a>"
+    );
+}
+
+#[test]
+fn normalize_rewrites_encoding_with_spaces_around_equals() {
+    for encoding in [
+        r#"encoding = "UTF-16""#,
+        "encoding ='utf-16'",
+        "encoding=\t\"utf-16\"",
+    ] {
+        let dir = tempfile::tempdir().expect("temp dir should be created");
+        let xml_path = dir.path().join("in.xml");
+        let xml_mut_path = dir.path().join("mut.xmlmut");
+        let xml = format!(
+            "<?xml version=\"1.0\" {}?>\r\n<a>\r\n  <b/>\r\n</a>",
+            encoding
+        );
+        fs::write(&xml_path, utf16le_with_bom(&xml)).unwrap();
+        fs::write(&xml_mut_path, "GET a/b\nSET [@c] = \"d\"").unwrap();
+
+        run_include(
+            &xml_mut_path,
+            &xml_path,
+            &["--normalize", "--format", "lossless"],
+        );
+
+        let xml_new = fs::read_to_string(&xml_path).expect("xml should be utf-8");
+        let expected = encoding
+            .replace("UTF-16", "utf-8")
+            .replace("utf-16", "utf-8");
+        assert_eq!(
+            xml_new,
+            format!(
+                "<?xml version=\"1.0\" {}?>\n<a>\n  <b c=\"d\"/>\n</a>",
+                expected
+            )
+        );
+    }
+}
+
+#[test]
+fn mixed_newlines_are_kept() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    fs::write(&xml_path, "<Project>\r\n  <V>1</V>\n  <W>1</W>\n</Project>\n").unwrap();
+    fs::write(&xml_mut_path, "GET Project/W\nSET [text] = \"2\"").unwrap();
+
+    run_include(&xml_mut_path, &xml_path, &["--format", "lossless"]);
+
+    let xml_new = fs::read_to_string(&xml_path).expect("xml should exist");
+    assert_eq!(xml_new, "<Project>\r\n  <V>1</V>\n  <W>2</W>\n</Project>\n");
+}
+
+#[test]
+fn mixed_newlines_become_lf_when_serialized() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    fs::write(&xml_path, "<Project>\r\n  <V>1</V>\n  <W>1</W>\n</Project>\n").unwrap();
+    fs::write(&xml_mut_path, "GET Project/W\nSET [text] = \"2\"").unwrap();
+
+    run_include(&xml_mut_path, &xml_path, &[]);
+
+    let xml_new = fs::read_to_string(&xml_path).expect("xml should exist");
+    assert_eq!(xml_new, "<Project>\n  <V>1</V>\n  <W>2</W>\n</Project>");
+}
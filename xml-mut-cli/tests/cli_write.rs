@@ -0,0 +1,86 @@
+use std::{fs, path::Path, process::Command, time::SystemTime};
+
+fn run_include(xml_mut_path: &Path, xml_path: &Path) {
+    let status = Command::new(env!("CARGO_BIN_EXE_xml-mut"))
+        .arg(xml_mut_path)
+        .arg("include")
+        .arg("-x")
+        .arg(xml_path)
+        .status()
+        .expect("xml-mut should run");
+    assert!(status.success());
+}
+
+#[test]
+fn write_replaces_file_and_keeps_permissions() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    fs::copy("tests/package_ref_version/in.xml", &xml_path).expect("copy should not fail");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&xml_path, fs::Permissions::from_mode(0o640))
+            .expect("set permissions should not fail");
+    }
+
+    run_include(Path::new("tests/package_ref_version/mut.xmlmut"), &xml_path);
+
+    let xml_expected =
+        fs::read_to_string("tests/package_ref_version/out.xml").expect("xml output should exist");
+    let xml_new = fs::read_to_string(&xml_path).expect("xml should exist");
+    assert_eq!(xml_expected, xml_new);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&xml_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    let file_count = fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(file_count, 1, "temp file should not be left behind");
+}
+
+#[test]
+fn write_skipped_when_output_is_identical() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    fs::write(&xml_path, r#"<Project><Item Include="a"/></Project>"#).unwrap();
+    fs::write(&xml_mut_path, "GET Project/Item\nSET [@Include] = \"a\"").unwrap();
+
+    let old_time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&xml_path)
+        .unwrap()
+        .set_modified(old_time)
+        .unwrap();
+
+    run_include(&xml_mut_path, &xml_path);
+
+    let modified = fs::metadata(&xml_path).unwrap().modified().unwrap();
+    assert_eq!(modified, old_time);
+}
+
+#[test]
+fn write_keeps_cdata_sections() {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let xml_path = dir.path().join("in.xml");
+    let xml_mut_path = dir.path().join("mut.xmlmut");
+    fs::write(
+        &xml_path,
+        "<Project><Script><![CDATA[a < b]]></Script><Note>a &lt; b</Note></Project>",
+    )
+    .unwrap();
+    fs::write(&xml_mut_path, "GET Project/Script\nSET [text] = \"a > b\"").unwrap();
+
+    run_include(&xml_mut_path, &xml_path);
+
+    let xml_new = fs::read_to_string(&xml_path).expect("xml should exist");
+    assert_eq!(
+        xml_new,
+        "<Project><Script><![CDATA[a > b]]></Script><Note>a &lt; b</Note></Project>"
+    );
+}
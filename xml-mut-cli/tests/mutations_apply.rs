@@ -1,7 +1,7 @@
 use std::fs;
-use xml_mut_data::{Mutation, Statement};
+use xml_mut_data::{Bindings, Mutation};
 use xml_mut_parse::prelude::*;
-use xml_mut_xot::prelude::Valueable;
+use xml_mut_xot::prelude::{resolve, Error, Locatable, Serializable, SerializeOptions, Valueable};
 
 fn with_input_expect_xml_mutation_output(
     xml_input_path: &str,
@@ -21,28 +21,27 @@ fn with_input_expect_xml_mutation_output(
         panic!("non_parsed should be empty but is {:#?}", non_parsed);
     }
 
-    let mutations = &grammar
-        .statements
-        .iter()
-        .filter_map(|s| match s {
-            Statement::Mutation(rep) => Some(rep),
-            _ => None,
-        })
-        .collect::<Vec<&Mutation>>();
+    let resolved = resolve(grammar, &Bindings::default()).expect("resolve should not fail");
+    let mutations = &resolved.mutations.iter().collect::<Vec<&Mutation>>();
 
     let mut xot = xot::Xot::new();
-    let root = xot.parse(&xml_string).expect("should be a valid xml");
+    let (root, span_info) = xot
+        .parse_with_span_info(&xml_string)
+        .expect("should be a valid xml");
+    let cdata = xot.cdata_nodes(&xml_string, &span_info, root);
     let doc_element_node = xot
         .document_element(root)
         .expect("should contain root element");
 
     let ops = xot
-        .get_operations_all(doc_element_node, mutations)
+        .get_operations_all(doc_element_node, mutations, &resolved.bindings)
         .expect("get operations should not fail");
 
     xot.apply_all(&ops).expect("apply should not fail");
 
-    let xml_new_string = xot.to_string(root).expect("apply should not fail");
+    let xml_new_string = xot
+        .serialize_with_cdata(root, &SerializeOptions::Plain, &cdata)
+        .expect("apply should not fail");
 
     //fs::write(xml_output_path, xml_new_string.clone()).expect("nu nesamone");
 
@@ -102,3 +101,224 @@ fn package_ref_version_single_line() {
         "tests/package_ref_version_single_line/out.xml",
     );
 }
+
+#[test]
+fn indent_new_elements_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/indent_new_elements/in.xml",
+        "tests/indent_new_elements/mut.xmlmut",
+        "tests/indent_new_elements/out.xml",
+    );
+}
+
+#[test]
+fn let_and_predicates_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/let_and_predicates/in.xml",
+        "tests/let_and_predicates/mut.xmlmut",
+        "tests/let_and_predicates/out.xml",
+    );
+}
+
+#[test]
+fn insert_element_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/insert_element/in.xml",
+        "tests/insert_element/mut.xmlmut",
+        "tests/insert_element/out.xml",
+    );
+}
+
+#[test]
+fn inner_outer_xml_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/inner_outer_xml/in.xml",
+        "tests/inner_outer_xml/mut.xmlmut",
+        "tests/inner_outer_xml/out.xml",
+    );
+}
+
+#[test]
+fn move_and_copy_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/move_and_copy/in.xml",
+        "tests/move_and_copy/mut.xmlmut",
+        "tests/move_and_copy/out.xml",
+    );
+}
+
+#[test]
+fn wrap_unwrap_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/wrap_unwrap/in.xml",
+        "tests/wrap_unwrap/mut.xmlmut",
+        "tests/wrap_unwrap/out.xml",
+    );
+}
+
+#[test]
+fn unwrap_mixed_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/unwrap_mixed/in.xml",
+        "tests/unwrap_mixed/mut.xmlmut",
+        "tests/unwrap_mixed/out.xml",
+    );
+}
+
+#[test]
+fn delete_all_children_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/delete_all_children/in.xml",
+        "tests/delete_all_children/mut.xmlmut",
+        "tests/delete_all_children/out.xml",
+    );
+}
+
+#[test]
+fn delete_all_and_insert_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/delete_all_and_insert/in.xml",
+        "tests/delete_all_and_insert/mut.xmlmut",
+        "tests/delete_all_and_insert/out.xml",
+    );
+}
+
+#[test]
+fn delete_then_set_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/delete_then_set/in.xml",
+        "tests/delete_then_set/mut.xmlmut",
+        "tests/delete_then_set/out.xml",
+    );
+}
+
+#[test]
+fn wrap_adjacent_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/wrap_adjacent/in.xml",
+        "tests/wrap_adjacent/mut.xmlmut",
+        "tests/wrap_adjacent/out.xml",
+    );
+}
+
+#[test]
+fn move_to_filtered_target_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/move_to_filtered_target/in.xml",
+        "tests/move_to_filtered_target/mut.xmlmut",
+        "tests/move_to_filtered_target/out.xml",
+    );
+}
+
+#[test]
+fn rename_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/rename/in.xml",
+        "tests/rename/mut.xmlmut",
+        "tests/rename/out.xml",
+    );
+}
+
+#[test]
+fn sort_children_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/sort_children/in.xml",
+        "tests/sort_children/mut.xmlmut",
+        "tests/sort_children/out.xml",
+    );
+}
+
+#[test]
+fn deduplicate_children_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/deduplicate_children/in.xml",
+        "tests/deduplicate_children/mut.xmlmut",
+        "tests/deduplicate_children/out.xml",
+    );
+}
+
+#[test]
+fn delete_empty_and_where_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/delete_empty_and_where/in.xml",
+        "tests/delete_empty_and_where/mut.xmlmut",
+        "tests/delete_empty_and_where/out.xml",
+    );
+}
+
+#[test]
+fn comment_and_pi_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/comment_and_pi/in.xml",
+        "tests/comment_and_pi/mut.xmlmut",
+        "tests/comment_and_pi/out.xml",
+    );
+}
+
+#[test]
+fn text_and_cdata_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/text_and_cdata/in.xml",
+        "tests/text_and_cdata/mut.xmlmut",
+        "tests/text_and_cdata/out.xml",
+    );
+}
+
+#[test]
+fn cdata_same_name_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/cdata_same_name/in.xml",
+        "tests/cdata_same_name/mut.xmlmut",
+        "tests/cdata_same_name/out.xml",
+    );
+}
+
+#[test]
+fn move_from_later_branch_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/move_from_later_branch/in.xml",
+        "tests/move_from_later_branch/mut.xmlmut",
+        "tests/move_from_later_branch/out.xml",
+    );
+}
+
+#[test]
+fn sort_and_deduplicate_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/sort_and_deduplicate/in.xml",
+        "tests/sort_and_deduplicate/mut.xmlmut",
+        "tests/sort_and_deduplicate/out.xml",
+    );
+}
+
+#[test]
+fn deduplicate_prerelease_mutation() {
+    with_input_expect_xml_mutation_output(
+        "tests/deduplicate_prerelease/in.xml",
+        "tests/deduplicate_prerelease/mut.xmlmut",
+        "tests/deduplicate_prerelease/out.xml",
+    );
+}
+
+#[test]
+fn move_to_ambiguous_target_fails() {
+    let xml_string = r#"<Project>
+  <ItemGroup>
+    <None Include="readme.md" />
+  </ItemGroup>
+  <ItemGroup />
+</Project>"#;
+    let (_, ref grammar) = xml_mut_grammar("GET Project\nMOVE ItemGroup/None TO ItemGroup LAST")
+        .expect("could not parse statements");
+    let resolved = resolve(grammar, &Bindings::default()).expect("resolve should not fail");
+    let mutations = &resolved.mutations.iter().collect::<Vec<&Mutation>>();
+
+    let mut xot = xot::Xot::new();
+    let root = xot.parse(xml_string).expect("should be a valid xml");
+    let doc_element_node = xot
+        .document_element(root)
+        .expect("should contain root element");
+    let result = xot.get_operations_all(doc_element_node, mutations, &resolved.bindings);
+
+    assert!(matches!(result, Err(Error::MoveTargetAmbiguous(_))));
+}
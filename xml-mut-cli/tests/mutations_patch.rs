@@ -0,0 +1,311 @@
+use std::fs;
+use xml_mut_data::{Bindings, Mutation, Statement};
+use xml_mut_parse::prelude::*;
+use xml_mut_xot::prelude::{resolve, Error, Patchable, Valueable};
+
+fn with_input_expect_xml_patch_output(
+    xml_input_path: &str,
+    xml_mut_path: &str,
+    xml_output_path: &str,
+) {
+    let xml_string = fs::read_to_string(xml_input_path).expect("xml file should exist");
+
+    let xml_expected_string =
+        fs::read_to_string(xml_output_path).expect("xml output file should exist");
+
+    let xml_mut_string = fs::read_to_string(xml_mut_path).expect("xml mutation file should exist");
+    let (non_parsed, ref grammar) =
+        xml_mut_grammar(xml_mut_string.as_str()).expect("could not parse statements");
+
+    if !non_parsed.is_empty() {
+        panic!("non_parsed should be empty but is {:#?}", non_parsed);
+    }
+
+    let resolved = resolve(grammar, &Bindings::default()).expect("resolve should not fail");
+    let mutations = &resolved.mutations.iter().collect::<Vec<&Mutation>>();
+
+    let mut xot = xot::Xot::new();
+    let (root, span_info) = xot
+        .parse_with_span_info(&xml_string)
+        .expect("should be a valid xml");
+    let doc_element_node = xot
+        .document_element(root)
+        .expect("should contain root element");
+
+    let ops = xot
+        .get_operations_all(doc_element_node, mutations, &resolved.bindings)
+        .expect("get operations should not fail");
+
+    let xml_new_string = xot
+        .patch_all(&xml_string, &span_info, &ops)
+        .expect("patch should not fail");
+
+    //fs::write(xml_output_path, xml_new_string.clone()).expect("nu nesamone");
+
+    assert_eq!(xml_expected_string, xml_new_string);
+}
+
+#[test]
+fn lossless_formatting_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/lossless_formatting/in.xml",
+        "tests/lossless_formatting/mut.xmlmut",
+        "tests/lossless_formatting/lossless.xml",
+    );
+}
+
+#[test]
+fn package_ref_version_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/package_ref_version/in.xml",
+        "tests/package_ref_version/mut.xmlmut",
+        "tests/package_ref_version/lossless.xml",
+    );
+}
+
+#[test]
+fn package_ref_to_project_ref_sub_node_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/package_ref_to_project_ref_sub_node/in.xml",
+        "tests/package_ref_to_project_ref_sub_node/mut.xmlmut",
+        "tests/package_ref_to_project_ref_sub_node/lossless.xml",
+    );
+}
+
+#[test]
+fn delete_with_preserve_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/delete_with_preserve/in.xml",
+        "tests/delete_with_preserve/mut.xmlmut",
+        "tests/delete_with_preserve/lossless.xml",
+    );
+}
+
+#[test]
+fn indent_new_elements_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/indent_new_elements/in.xml",
+        "tests/indent_new_elements/mut.xmlmut",
+        "tests/indent_new_elements/lossless.xml",
+    );
+}
+
+#[test]
+fn insert_element_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/insert_element/in.xml",
+        "tests/insert_element/mut.xmlmut",
+        "tests/insert_element/lossless.xml",
+    );
+}
+
+#[test]
+fn inner_outer_xml_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/inner_outer_xml/in.xml",
+        "tests/inner_outer_xml/mut.xmlmut",
+        "tests/inner_outer_xml/lossless.xml",
+    );
+}
+
+#[test]
+fn move_and_copy_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/move_and_copy/in.xml",
+        "tests/move_and_copy/mut.xmlmut",
+        "tests/move_and_copy/lossless.xml",
+    );
+}
+
+#[test]
+fn wrap_unwrap_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/wrap_unwrap/in.xml",
+        "tests/wrap_unwrap/mut.xmlmut",
+        "tests/wrap_unwrap/lossless.xml",
+    );
+}
+
+#[test]
+fn unwrap_mixed_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/unwrap_mixed/in.xml",
+        "tests/unwrap_mixed/mut.xmlmut",
+        "tests/unwrap_mixed/lossless.xml",
+    );
+}
+
+#[test]
+fn delete_all_children_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/delete_all_children/in.xml",
+        "tests/delete_all_children/mut.xmlmut",
+        "tests/delete_all_children/lossless.xml",
+    );
+}
+
+#[test]
+fn delete_all_and_insert_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/delete_all_and_insert/in.xml",
+        "tests/delete_all_and_insert/mut.xmlmut",
+        "tests/delete_all_and_insert/lossless.xml",
+    );
+}
+
+#[test]
+fn delete_then_set_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/delete_then_set/in.xml",
+        "tests/delete_then_set/mut.xmlmut",
+        "tests/delete_then_set/lossless.xml",
+    );
+}
+
+#[test]
+fn wrap_adjacent_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/wrap_adjacent/in.xml",
+        "tests/wrap_adjacent/mut.xmlmut",
+        "tests/wrap_adjacent/lossless.xml",
+    );
+}
+
+#[test]
+fn move_to_filtered_target_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/move_to_filtered_target/in.xml",
+        "tests/move_to_filtered_target/mut.xmlmut",
+        "tests/move_to_filtered_target/lossless.xml",
+    );
+}
+
+#[test]
+fn rename_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/rename/in.xml",
+        "tests/rename/mut.xmlmut",
+        "tests/rename/lossless.xml",
+    );
+}
+
+#[test]
+fn sort_children_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/sort_children/in.xml",
+        "tests/sort_children/mut.xmlmut",
+        "tests/sort_children/lossless.xml",
+    );
+}
+
+#[test]
+fn deduplicate_children_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/deduplicate_children/in.xml",
+        "tests/deduplicate_children/mut.xmlmut",
+        "tests/deduplicate_children/lossless.xml",
+    );
+}
+
+#[test]
+fn delete_empty_and_where_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/delete_empty_and_where/in.xml",
+        "tests/delete_empty_and_where/mut.xmlmut",
+        "tests/delete_empty_and_where/lossless.xml",
+    );
+}
+
+#[test]
+fn comment_and_pi_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/comment_and_pi/in.xml",
+        "tests/comment_and_pi/mut.xmlmut",
+        "tests/comment_and_pi/lossless.xml",
+    );
+}
+
+#[test]
+fn text_and_cdata_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/text_and_cdata/in.xml",
+        "tests/text_and_cdata/mut.xmlmut",
+        "tests/text_and_cdata/lossless.xml",
+    );
+}
+
+#[test]
+fn cdata_same_name_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/cdata_same_name/in.xml",
+        "tests/cdata_same_name/mut.xmlmut",
+        "tests/cdata_same_name/lossless.xml",
+    );
+}
+
+#[test]
+fn move_from_later_branch_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/move_from_later_branch/in.xml",
+        "tests/move_from_later_branch/mut.xmlmut",
+        "tests/move_from_later_branch/lossless.xml",
+    );
+}
+
+#[test]
+fn sort_and_deduplicate_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/sort_and_deduplicate/in.xml",
+        "tests/sort_and_deduplicate/mut.xmlmut",
+        "tests/sort_and_deduplicate/lossless.xml",
+    );
+}
+
+#[test]
+fn deduplicate_prerelease_patch() {
+    with_input_expect_xml_patch_output(
+        "tests/deduplicate_prerelease/in.xml",
+        "tests/deduplicate_prerelease/mut.xmlmut",
+        "tests/deduplicate_prerelease/lossless.xml",
+    );
+}
+
+#[test]
+fn insert_into_deleted_node_patch_fails() {
+    let xml_string = r#"<Project>
+  <ItemGroup>
+    <PackageReference Include="Serilog" />
+  </ItemGroup>
+</Project>"#;
+    let (_, ref grammar) = xml_mut_grammar(
+        "GET Project/ItemGroup
+INSERT <Version/> INTO PackageReference AS LAST
+
+GET Project/ItemGroup
+DELETE PackageReference",
+    )
+    .expect("could not parse statements");
+    let mutations = &grammar
+        .statements
+        .iter()
+        .filter_map(|s| match s {
+            Statement::Mutation(rep) => Some(rep.as_ref()),
+            _ => None,
+        })
+        .collect::<Vec<&Mutation>>();
+
+    let mut xot = xot::Xot::new();
+    let (root, span_info) = xot
+        .parse_with_span_info(xml_string)
+        .expect("should be a valid xml");
+    let doc_element_node = xot
+        .document_element(root)
+        .expect("should contain root element");
+    let ops = xot
+        .get_operations_all(doc_element_node, mutations, &Bindings::default())
+        .expect("get operations should not fail");
+
+    let result = xot.patch_all(xml_string, &span_info, &ops);
+
+    assert!(matches!(result, Err(Error::OverlappingPatches)));
+}
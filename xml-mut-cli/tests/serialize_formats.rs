@@ -0,0 +1,91 @@
+use std::fs;
+use xml_mut_data::{Bindings, Mutation, Statement};
+use xml_mut_parse::prelude::*;
+use xml_mut_xot::prelude::{
+    CanonicalOptions, PrettyOptions, Serializable, SerializeOptions, Valueable,
+};
+
+fn with_input_expect_xml_serialize_output(
+    xml_input_path: &str,
+    xml_mut_path: &str,
+    xml_output_path: &str,
+    options: SerializeOptions,
+) {
+    let xml_string = fs::read_to_string(xml_input_path).expect("xml file should exist");
+
+    let xml_expected_string =
+        fs::read_to_string(xml_output_path).expect("xml output file should exist");
+
+    let xml_mut_string = fs::read_to_string(xml_mut_path).expect("xml mutation file should exist");
+    let (non_parsed, ref grammar) =
+        xml_mut_grammar(xml_mut_string.as_str()).expect("could not parse statements");
+
+    if !non_parsed.is_empty() {
+        panic!("non_parsed should be empty but is {:#?}", non_parsed);
+    }
+
+    let mutations = &grammar
+        .statements
+        .iter()
+        .filter_map(|s| match s {
+            Statement::Mutation(rep) => Some(rep.as_ref()),
+            _ => None,
+        })
+        .collect::<Vec<&Mutation>>();
+
+    let mut xot = xot::Xot::new();
+    let root = xot.parse(&xml_string).expect("should be a valid xml");
+    let doc_element_node = xot
+        .document_element(root)
+        .expect("should contain root element");
+
+    let ops = xot
+        .get_operations_all(doc_element_node, mutations, &Bindings::default())
+        .expect("get operations should not fail");
+
+    xot.apply_all(&ops).expect("apply should not fail");
+
+    let xml_new_string = xot
+        .serialize_with(root, &options)
+        .expect("serialize should not fail");
+
+    //fs::write(xml_output_path, xml_new_string.clone()).expect("nu nesamone");
+
+    assert_eq!(xml_expected_string, xml_new_string);
+}
+
+#[test]
+fn pretty_serialize() {
+    with_input_expect_xml_serialize_output(
+        "tests/pretty_and_canonical/in.xml",
+        "tests/pretty_and_canonical/mut.xmlmut",
+        "tests/pretty_and_canonical/pretty.xml",
+        SerializeOptions::Pretty(PrettyOptions {
+            indent: "    ".to_string(),
+            attributes_per_line: Some(2),
+        }),
+    );
+}
+
+#[test]
+fn canonical_serialize() {
+    with_input_expect_xml_serialize_output(
+        "tests/pretty_and_canonical/in.xml",
+        "tests/pretty_and_canonical/mut.xmlmut",
+        "tests/pretty_and_canonical/c14n.xml",
+        SerializeOptions::Canonical(CanonicalOptions::default()),
+    );
+}
+
+#[test]
+fn exclusive_canonical_serialize() {
+    with_input_expect_xml_serialize_output(
+        "tests/pretty_and_canonical/in.xml",
+        "tests/pretty_and_canonical/mut.xmlmut",
+        "tests/pretty_and_canonical/exc-c14n.xml",
+        SerializeOptions::Canonical(CanonicalOptions {
+            exclusive: true,
+            with_comments: true,
+        }),
+    );
+}
@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use std::{collections::BTreeMap, fmt, ops::Deref};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NodePath<'a> {
@@ -13,12 +13,65 @@ impl<'a> Deref for NodePath<'a> {
     }
 }
 
+impl<'a> NodePath<'a> {
+    /// The rest of the path after `prefix`, `None` when it does not start
+    /// with it
+    pub fn relative_to(&self, prefix: &NodePath) -> Option<NodePath<'a>> {
+        let starts_with = self.path.len() >= prefix.len()
+            && self.path.iter().zip(prefix.iter()).all(|(a, b)| a == b);
+        starts_with.then(|| NodePath {
+            path: self.path[prefix.len()..].to_vec(),
+        })
+    }
+}
+
+impl fmt::Display for NodePath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.join("/"))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValueSelector<'a> {
     Attribute(&'a str),
+    /// Direct text of the element, all of its text children joined
     Text,
+    /// Text of the element and all of its descendants, like XPath `string()`
+    String,
     Tail,
     Name,
+    /// Xml of the children
+    Inner,
+    /// Xml of the element itself
+    Outer,
+    /// Content of the first comment in the element
+    Comment,
+    /// Data of the first processing instruction with the target in the
+    /// element
+    ProcessingInstruction(&'a str),
+}
+
+impl ValueSelector<'_> {
+    /// Whether the value is xml markup rather than text
+    pub fn is_xml(&self) -> bool {
+        matches!(self, ValueSelector::Inner | ValueSelector::Outer)
+    }
+}
+
+impl fmt::Display for ValueSelector<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSelector::Attribute(name) => write!(f, "[@{}]", name),
+            ValueSelector::Text => write!(f, "[text]"),
+            ValueSelector::String => write!(f, "[string]"),
+            ValueSelector::Tail => write!(f, "[tail]"),
+            ValueSelector::Name => write!(f, "[name]"),
+            ValueSelector::Inner => write!(f, "[inner]"),
+            ValueSelector::Outer => write!(f, "[outer]"),
+            ValueSelector::Comment => write!(f, "[comment]"),
+            ValueSelector::ProcessingInstruction(target) => write!(f, "[pi:{}]", target),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,10 +80,65 @@ pub struct ValuePath<'a> {
     pub selector: ValueSelector<'a>,
 }
 
+impl<'a> ValuePath<'a> {
+    /// The same value of the node at `prefix`, `None` when the path does not
+    /// start with it
+    pub fn relative_to(&self, prefix: &NodePath) -> Option<ValuePath<'a>> {
+        Some(ValuePath {
+            node_path: self.node_path.relative_to(prefix)?,
+            selector: self.selector.clone(),
+        })
+    }
+}
+
+/// Written back the way it is parsed, e.g. `Version[text]`
+impl fmt::Display for ValuePath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.node_path, self.selector)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValueVariant<'a> {
     Selector(ValuePath<'a>),
     LiteralString(&'a str),
+    /// Name of a `$name` parameter without the `$`
+    Parameter(&'a str),
+    /// `xml"<a b='1'/>"`, xml markup
+    Xml(&'a str),
+}
+
+impl ValueVariant<'_> {
+    /// Whether the value is xml markup rather than text
+    pub fn is_xml(&self) -> bool {
+        match self {
+            ValueVariant::Selector(path) => path.selector.is_xml(),
+            ValueVariant::Xml(_) => true,
+            ValueVariant::LiteralString(_) | ValueVariant::Parameter(_) => false,
+        }
+    }
+}
+
+/// Values of `$name` parameters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bindings {
+    values: BTreeMap<String, String>,
+}
+
+impl Bindings {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// Adds all values of `other`, replacing values of the same name
+    pub fn extend(&mut self, other: &Bindings) {
+        self.values
+            .extend(other.values.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -39,6 +147,16 @@ pub enum PathVariant<'a> {
     Value(ValuePath<'a>),
 }
 
+impl<'a> PathVariant<'a> {
+    /// Path of the node itself or of the node holding the value
+    pub fn node_path(&self) -> &NodePath<'a> {
+        match self {
+            PathVariant::Node(path) => path,
+            PathVariant::Value(value) => &value.node_path,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ValueAssignment<'a> {
     pub target: ValuePath<'a>,
@@ -57,10 +175,64 @@ pub struct PredicateEquals<'a> {
     pub right_side: ValueVariant<'a>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateStartsWith<'a> {
+    pub left_side: ValuePath<'a>,
+    pub right_side: ValueVariant<'a>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Predicate<'a> {
     Exists(PredicateExists<'a>),
     Equals(PredicateEquals<'a>),
+    StartsWith(PredicateStartsWith<'a>),
+    /// Name of a predicate defined with `DEFINE PREDICATE`
+    Reference(&'a str),
+}
+
+impl<'a> Predicate<'a> {
+    /// Name of the `$name` parameter the predicate compares with, if any
+    pub fn parameter(&self) -> Option<&'a str> {
+        let right_side = match self {
+            Predicate::Equals(p) => &p.right_side,
+            Predicate::StartsWith(p) => &p.right_side,
+            Predicate::Exists(_) | Predicate::Reference(_) => return None,
+        };
+        match right_side {
+            ValueVariant::Parameter(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The same predicate checked on the node at `prefix`, `None` when the
+    /// path it tests does not start with it. Selectors compared with keep
+    /// their paths unless they start with `prefix` too.
+    pub fn relative_to(&self, prefix: &NodePath) -> Option<Predicate<'a>> {
+        let right_side = |variant: &ValueVariant<'a>| match variant {
+            ValueVariant::Selector(value) => {
+                ValueVariant::Selector(value.relative_to(prefix).unwrap_or_else(|| value.clone()))
+            }
+            variant => variant.clone(),
+        };
+        match self {
+            Predicate::Exists(p) => Some(Predicate::Exists(PredicateExists {
+                exists_word: p.exists_word,
+                path: match &p.path {
+                    PathVariant::Node(path) => PathVariant::Node(path.relative_to(prefix)?),
+                    PathVariant::Value(value) => PathVariant::Value(value.relative_to(prefix)?),
+                },
+            })),
+            Predicate::Equals(p) => Some(Predicate::Equals(PredicateEquals {
+                left_side: p.left_side.relative_to(prefix)?,
+                right_side: right_side(&p.right_side),
+            })),
+            Predicate::StartsWith(p) => Some(Predicate::StartsWith(PredicateStartsWith {
+                left_side: p.left_side.relative_to(prefix)?,
+                right_side: right_side(&p.right_side),
+            })),
+            Predicate::Reference(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -102,23 +274,325 @@ impl<'a> SetClause<'a> {
     }
 }
 
+/// `[EMPTY] {path_variant} [WHERE {predicate} and ...]`, a target with
+/// conditions deletes every node at the path that meets them, a target
+/// without them the first node and it must exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteTarget<'a> {
+    pub path: PathVariant<'a>,
+    /// Only elements without child elements and with nothing but whitespace
+    /// text are deleted
+    pub empty: bool,
+    /// Predicates that test a path starting with the target path are checked
+    /// on each target node, the others on the matched node
+    pub where_clause: Option<WhereClause<'a>>,
+}
+
+impl DeleteTarget<'_> {
+    pub fn is_conditional(&self) -> bool {
+        self.empty || self.where_clause.is_some()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DeleteClause<'a> {
     pub delete_word: &'a str,
-    pub targets: Vec<PathVariant<'a>>,
+    pub targets: Vec<DeleteTarget<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectClause<'a> {
+    pub select_word: &'a str,
+    pub values: Vec<ValuePath<'a>>,
+}
+
+/// `<Name attr=value ...>text</Name>`, an element created by `INSERT`.
+/// Values are literals, parameters or values of the matched node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementTemplate<'a> {
+    pub name: &'a str,
+    pub attributes: Vec<(&'a str, ValueVariant<'a>)>,
+    pub text: Option<ValueVariant<'a>>,
+}
+
+impl<'a> ElementTemplate<'a> {
+    /// Attribute values followed by the text value
+    pub fn values(&self) -> impl Iterator<Item = &ValueVariant<'a>> {
+        self.attributes.iter().map(|(_, v)| v).chain(&self.text)
+    }
+}
+
+/// Where new nodes go, paths are relative to the matched node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertPosition<'a> {
+    /// First child of the node at the path, an empty path is the matched node
+    First(NodePath<'a>),
+    /// Last child of the node at the path, an empty path is the matched node
+    Last(NodePath<'a>),
+    /// Sibling right before the node at the path
+    Before(NodePath<'a>),
+    /// Sibling right after the node at the path
+    After(NodePath<'a>),
+}
+
+impl<'a> InsertPosition<'a> {
+    pub fn node_path(&self) -> &NodePath<'a> {
+        match self {
+            InsertPosition::First(path)
+            | InsertPosition::Last(path)
+            | InsertPosition::Before(path)
+            | InsertPosition::After(path) => path,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertClause<'a> {
+    pub insert_word: &'a str,
+    pub element: ElementTemplate<'a>,
+    pub position: InsertPosition<'a>,
+}
+
+impl<'a> InsertClause<'a> {
+    /// Nodes without the position path or a value source are not matched
+    pub fn imply_predicates(&self) -> Vec<Predicate<'a>> {
+        let position = self.position.node_path();
+        let position = (!position.is_empty()).then(|| PathVariant::Node(position.clone()));
+        let values = self.element.values().filter_map(|v| match v {
+            ValueVariant::Selector(value_path) if !value_path.node_path.is_empty() => {
+                Some(PathVariant::Value(value_path.clone()))
+            }
+            _ => None,
+        });
+        position
+            .into_iter()
+            .chain(values)
+            .map(|path| {
+                Predicate::Exists(PredicateExists {
+                    exists_word: "exists",
+                    path,
+                })
+            })
+            .collect()
+    }
+}
+
+/// `MOVE path TO path [FIRST|LAST|BEFORE|AFTER]` or the same with `COPY`,
+/// relocates every node at the source path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveClause<'a> {
+    pub move_word: &'a str,
+    /// Copies are placed at every node of the position path, moved nodes
+    /// at the first one
+    pub copy: bool,
+    /// Nodes to relocate, an empty path is the matched node
+    pub source: NodePath<'a>,
+    pub position: InsertPosition<'a>,
+    /// Predicates that test a path starting with the position path are
+    /// checked on each node of it, the others on the matched node
+    pub where_clause: Option<WhereClause<'a>>,
+}
+
+impl<'a> MoveClause<'a> {
+    /// Nodes without the source or the position path are not matched
+    pub fn imply_predicates(&self) -> Vec<Predicate<'a>> {
+        [&self.source, self.position.node_path()]
+            .into_iter()
+            .filter(|path| !path.is_empty())
+            .map(|path| {
+                Predicate::Exists(PredicateExists {
+                    exists_word: "exists",
+                    path: PathVariant::Node(path.clone()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// `[@Old] TO [@New]` or `Child TO NewChild`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename<'a> {
+    /// An attribute or an element, an empty node path is the matched node
+    pub source: PathVariant<'a>,
+    /// New name of the attribute or the element
+    pub name: &'a str,
+}
+
+/// `RENAME {rename}, {rename}, ...`, sources that do not exist are left
+/// alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameClause<'a> {
+    pub rename_word: &'a str,
+    pub renames: Vec<Rename<'a>>,
+}
+
+/// `WRAP IN <Outer attr=value><Inner>`, puts the matched node into new
+/// elements, outermost first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrapClause<'a> {
+    pub wrap_word: &'a str,
+    /// Elements without text, the matched node goes into the last one
+    pub wrappers: Vec<ElementTemplate<'a>>,
+}
+
+impl<'a> WrapClause<'a> {
+    /// Nodes without a value the wrappers take from a sub node are not matched
+    pub fn imply_predicates(&self) -> Vec<Predicate<'a>> {
+        self.wrappers
+            .iter()
+            .flat_map(|wrapper| wrapper.values())
+            .filter_map(|v| match v {
+                ValueVariant::Selector(value_path) if !value_path.node_path.is_empty() => {
+                    Some(Predicate::Exists(PredicateExists {
+                        exists_word: "exists",
+                        path: PathVariant::Value(value_path.clone()),
+                    }))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// `UNWRAP`, replaces the matched node with its children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwrapClause<'a> {
+    pub unwrap_word: &'a str,
+}
+
+/// `SORT Child BY [@Key] [ASC|DESC] [CASE INSENSITIVE]`, reorders the
+/// elements at the path among their siblings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortClause<'a> {
+    pub sort_word: &'a str,
+    pub path: NodePath<'a>,
+    /// Value of each sorted element to compare, elements without it are the
+    /// smallest
+    pub key: ValuePath<'a>,
+    pub descending: bool,
+    pub case_insensitive: bool,
+}
+
+/// Which one of the elements with the same key is kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeduplicateKeep<'a> {
+    First,
+    Last,
+    /// The one with the highest version at the path, compared segment by
+    /// segment
+    Highest(ValuePath<'a>),
+}
+
+/// `DEDUPLICATE Child BY [@Key] [KEEP FIRST|LAST|HIGHEST [@Version]]`,
+/// deletes the siblings at the path that have the same key as another one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeduplicateClause<'a> {
+    pub deduplicate_word: &'a str,
+    pub path: NodePath<'a>,
+    /// Value the duplicates share, elements without it are always kept
+    pub key: ValuePath<'a>,
+    pub keep: DeduplicateKeep<'a>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Mutation<'a> {
+    /// Label given with `MUTATION name:` or a `/* @name name */` comment
+    pub name: Option<&'a str>,
     pub get_clause: GetClause<'a>,
     pub where_clause: Option<WhereClause<'a>>,
+    pub select_clause: Option<SelectClause<'a>>,
     pub set_clause: Option<SetClause<'a>>,
+    pub rename_clause: Option<RenameClause<'a>>,
+    pub insert_clause: Option<InsertClause<'a>>,
+    pub move_clause: Option<MoveClause<'a>>,
+    pub wrap_clause: Option<WrapClause<'a>>,
+    pub unwrap_clause: Option<UnwrapClause<'a>>,
+    pub sort_clause: Option<SortClause<'a>>,
+    pub deduplicate_clause: Option<DeduplicateClause<'a>>,
     pub delete_clause: Option<DeleteClause<'a>>,
 }
+
+impl<'a> Mutation<'a> {
+    /// A query only selects values and never changes the xml
+    pub fn is_query(&self) -> bool {
+        self.set_clause.is_none()
+            && self.rename_clause.is_none()
+            && self.insert_clause.is_none()
+            && self.move_clause.is_none()
+            && self.wrap_clause.is_none()
+            && self.unwrap_clause.is_none()
+            && self.sort_clause.is_none()
+            && self.deduplicate_clause.is_none()
+            && self.delete_clause.is_none()
+    }
+
+    /// Predicates of the mutation `WHERE` and of the clause ones
+    pub fn predicates(&self) -> impl Iterator<Item = &Predicate<'a>> {
+        let deleted = self.delete_clause.iter().flat_map(|d| &d.targets);
+        let moved = self
+            .move_clause
+            .iter()
+            .filter_map(|m| m.where_clause.as_ref());
+        self.where_clause
+            .iter()
+            .chain(moved)
+            .chain(deleted.filter_map(|t| t.where_clause.as_ref()))
+            .flat_map(|w| w.predicates.iter())
+    }
+
+    /// Names of all `$name` parameters in the order they are used
+    pub fn parameters(&self) -> Vec<&'a str> {
+        let predicates = self.predicates();
+        let assignments = self.set_clause.iter().flat_map(|s| s.assignments.iter());
+        let inserted = self.insert_clause.iter().flat_map(|i| i.element.values());
+        let wrappers = self.wrap_clause.iter().flat_map(|w| &w.wrappers);
+        predicates
+            .filter_map(|p| p.parameter())
+            .chain(
+                assignments
+                    .map(|a| &a.source)
+                    .chain(inserted)
+                    .chain(wrappers.flat_map(|w| w.values()))
+                    .filter_map(|v| match v {
+                        ValueVariant::Parameter(name) => Some(*name),
+                        _ => None,
+                    }),
+            )
+            .collect()
+    }
+}
+/// `LET $name = "value"`, a parameter value given in the mutation file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetStatement<'a> {
+    pub let_word: &'a str,
+    /// Parameter name without the `$`
+    pub name: &'a str,
+    pub value: &'a str,
+}
+
+/// `DEFINE PREDICATE name AS {predicate} and ...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateDefinition<'a> {
+    pub define_word: &'a str,
+    pub name: &'a str,
+    pub predicates: Vec<Predicate<'a>>,
+}
+
+/// `INCLUDE "path"`, statements of another mutation file, the path is
+/// relative to the including file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludeStatement<'a> {
+    pub include_word: &'a str,
+    pub path: &'a str,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Statement<'a> {
-    Mutation(Mutation<'a>),
+    Mutation(Box<Mutation<'a>>),
     Comment(&'a str),
+    Let(LetStatement<'a>),
+    DefinePredicate(PredicateDefinition<'a>),
+    Include(IncludeStatement<'a>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
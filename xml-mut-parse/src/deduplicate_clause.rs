@@ -0,0 +1,39 @@
+use crate::prelude::*;
+use nom::{
+    branch::alt, bytes::complete::tag_no_case, character::complete::multispace1, combinator::opt,
+    sequence::preceded, IResult, Parser,
+};
+use xml_mut_data::{DeduplicateClause, DeduplicateKeep};
+
+/// `KEEP FIRST`, `KEEP LAST` or `KEEP HIGHEST [@Version]`
+pub fn deduplicate_keep(s: &str) -> IResult<&str, DeduplicateKeep<'_>> {
+    let (s, _) = tag_no_case("keep")(s)?;
+    let (s, _) = multispace1(s)?;
+    alt((
+        tag_no_case("first").map(|_| DeduplicateKeep::First),
+        tag_no_case("last").map(|_| DeduplicateKeep::Last),
+        preceded((tag_no_case("highest"), multispace1), value_path).map(DeduplicateKeep::Highest),
+    ))
+    .parse(s)
+}
+
+pub fn deduplicate_clause(s: &str) -> IResult<&str, DeduplicateClause<'_>> {
+    let (s, deduplicate_word) = tag_no_case("deduplicate")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, path) = node_path(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("by")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, key) = value_path(s)?;
+    let (s, keep) = opt(preceded(multispace1, deduplicate_keep)).parse(s)?;
+
+    Ok((
+        s,
+        DeduplicateClause {
+            deduplicate_word,
+            path,
+            key,
+            keep: keep.unwrap_or(DeduplicateKeep::First),
+        },
+    ))
+}
@@ -1,14 +1,47 @@
-use crate::{prelude::comma_surounded_mulispace01, where_clause::path_variant};
+use crate::{
+    prelude::{comma_surounded_mulispace01, node_path, where_clause},
+    where_clause::path_variant,
+};
 use nom::{
-    bytes::complete::tag_no_case, character::complete::multispace1, multi::separated_list1,
+    bytes::complete::tag_no_case,
+    character::complete::multispace1,
+    combinator::opt,
+    multi::separated_list1,
+    sequence::{preceded, terminated},
     IResult, Parser,
 };
-use xml_mut_data::DeleteClause;
+use xml_mut_data::{DeleteClause, DeleteTarget, PathVariant};
+
+/// `EMPTY {node_path}`, `{path_variant}` and either followed by `WHERE`
+pub fn delete_target(s: &str) -> IResult<&str, DeleteTarget<'_>> {
+    let (s, empty) = opt(preceded(
+        terminated(tag_no_case("empty"), multispace1),
+        node_path,
+    ))
+    .parse(s)?;
+    let (s, path, empty) = match empty {
+        Some(path) => (s, PathVariant::Node(path), true),
+        None => {
+            let (s, path) = path_variant(s)?;
+            (s, path, false)
+        }
+    };
+    let (s, where_clause) = opt(preceded(multispace1, where_clause)).parse(s)?;
+
+    Ok((
+        s,
+        DeleteTarget {
+            path,
+            empty,
+            where_clause,
+        },
+    ))
+}
 
-pub fn delete_clause(s: &str) -> IResult<&str, DeleteClause> {
+pub fn delete_clause(s: &str) -> IResult<&str, DeleteClause<'_>> {
     let (s, delete_word) = tag_no_case("delete")(s)?;
     let (s, _) = multispace1(s)?;
-    let (s, targets) = separated_list1(comma_surounded_mulispace01, path_variant).parse(s)?;
+    let (s, targets) = separated_list1(comma_surounded_mulispace01, delete_target).parse(s)?;
 
     Ok((
         s,
@@ -12,12 +12,12 @@ pub fn is_valid_in_xml_node_name(s: char) -> bool {
     s.is_alphanumeric() || s == '_' || s == '-' || s == '.' || s == ':'
 }
 
-pub fn node_path(s: &str) -> IResult<&str, NodePath> {
+pub fn node_path(s: &str) -> IResult<&str, NodePath<'_>> {
     let (s, path) = separated_list1(tag("/"), take_while1(is_valid_in_xml_node_name)).parse(s)?;
     Ok((s, NodePath { path }))
 }
 
-pub fn get_clause(s: &str) -> IResult<&str, GetClause> {
+pub fn get_clause(s: &str) -> IResult<&str, GetClause<'_>> {
     let (s, get_word) = tag_no_case("get")(s)?;
     let (s, _) = multispace1(s)?;
     let (s, node_selector) = node_path(s)?;
@@ -0,0 +1,11 @@
+use crate::set_clause::literal_quoted_string;
+use nom::{bytes::complete::tag_no_case, character::complete::multispace1, IResult};
+use xml_mut_data::IncludeStatement;
+
+pub fn include_statement(s: &str) -> IResult<&str, IncludeStatement<'_>> {
+    let (s, include_word) = tag_no_case("include")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, path) = literal_quoted_string(s)?;
+
+    Ok((s, IncludeStatement { include_word, path }))
+}
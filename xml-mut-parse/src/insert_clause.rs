@@ -0,0 +1,116 @@
+use crate::{get_clause::is_valid_in_xml_node_name, prelude::*};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while1},
+    character::complete::{multispace0, multispace1},
+    combinator::opt,
+    multi::many0,
+    sequence::preceded,
+    IResult, Parser,
+};
+use xml_mut_data::{ElementTemplate, InsertClause, InsertPosition, NodePath, ValueVariant};
+
+pub(crate) fn element_attribute(s: &str) -> IResult<&str, (&str, ValueVariant<'_>)> {
+    let (s, name) = take_while1(is_valid_in_xml_node_name)(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = tag("=")(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, value) = value_variant(s)?;
+    Ok((s, (name, value)))
+}
+
+/// `<Name attr="literal" other=[@Source]/>` or `<Name>$text</Name>`
+pub fn element_template(s: &str) -> IResult<&str, ElementTemplate<'_>> {
+    let (s, _) = tag("<")(s)?;
+    let (s, name) = take_while1(is_valid_in_xml_node_name)(s)?;
+    let (s, attributes) = many0(preceded(multispace1, element_attribute)).parse(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, empty) = opt(tag("/>")).parse(s)?;
+    if empty.is_some() {
+        return Ok((
+            s,
+            ElementTemplate {
+                name,
+                attributes,
+                text: None,
+            },
+        ));
+    }
+    let (s, _) = tag(">")(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, text) = opt(value_variant).parse(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = tag("</")(s)?;
+    let (s, _) = tag(name)(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = tag(">")(s)?;
+
+    Ok((
+        s,
+        ElementTemplate {
+            name,
+            attributes,
+            text,
+        },
+    ))
+}
+
+/// A node path or `.` for the matched node itself
+pub fn position_path(s: &str) -> IResult<&str, NodePath<'_>> {
+    alt((tag(".").map(|_| NodePath { path: vec![] }), node_path)).parse(s)
+}
+
+/// `AS FIRST` or `AS LAST`, true for first
+fn as_first_or_last(s: &str) -> IResult<&str, bool> {
+    let (s, _) = tag_no_case("as")(s)?;
+    let (s, _) = multispace1(s)?;
+    alt((
+        tag_no_case("first").map(|_| true),
+        tag_no_case("last").map(|_| false),
+    ))
+    .parse(s)
+}
+
+/// `INTO path [AS FIRST|LAST]`, `AS FIRST|LAST` or `BEFORE|AFTER path`
+pub fn insert_position(s: &str) -> IResult<&str, InsertPosition<'_>> {
+    let (s, sibling) = opt((
+        alt((tag_no_case("before"), tag_no_case("after"))),
+        preceded(multispace1, position_path),
+    ))
+    .parse(s)?;
+    if let Some((word, path)) = sibling {
+        return Ok(if word.eq_ignore_ascii_case("before") {
+            (s, InsertPosition::Before(path))
+        } else {
+            (s, InsertPosition::After(path))
+        });
+    }
+
+    let (s, into) = opt(preceded((tag_no_case("into"), multispace1), position_path)).parse(s)?;
+    let (s, first) = match into {
+        Some(_) => opt(preceded(multispace1, as_first_or_last)).parse(s)?,
+        None => as_first_or_last(s).map(|(s, first)| (s, Some(first)))?,
+    };
+    let path = into.unwrap_or(NodePath { path: vec![] });
+    Ok(if first == Some(true) {
+        (s, InsertPosition::First(path))
+    } else {
+        (s, InsertPosition::Last(path))
+    })
+}
+
+pub fn insert_clause(s: &str) -> IResult<&str, InsertClause<'_>> {
+    let (s, insert_word) = tag_no_case("insert")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, element) = element_template(s)?;
+    let (s, position) = opt(preceded(multispace1, insert_position)).parse(s)?;
+
+    Ok((
+        s,
+        InsertClause {
+            insert_word,
+            element,
+            position: position.unwrap_or(InsertPosition::Last(NodePath { path: vec![] })),
+        },
+    ))
+}
@@ -0,0 +1,26 @@
+use crate::set_clause::{literal_quoted_string, parameter};
+use nom::{
+    bytes::complete::{tag, tag_no_case},
+    character::complete::multispace1,
+    IResult,
+};
+use xml_mut_data::LetStatement;
+
+pub fn let_statement(s: &str) -> IResult<&str, LetStatement<'_>> {
+    let (s, let_word) = tag_no_case("let")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, name) = parameter(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag("=")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, value) = literal_quoted_string(s)?;
+
+    Ok((
+        s,
+        LetStatement {
+            let_word,
+            name,
+            value,
+        },
+    ))
+}
@@ -1,15 +1,37 @@
+mod deduplicate_clause;
 mod delete_clause;
 mod get_clause;
+mod include_statement;
+mod insert_clause;
+mod let_statement;
+mod move_clause;
 mod mutation;
+mod predicate_definition;
+mod rename_clause;
+mod select_clause;
 mod set_clause;
+mod sort_clause;
 mod statement;
+mod validate;
 mod where_clause;
+mod wrap_clause;
 
 pub mod prelude {
+    pub use super::deduplicate_clause::*;
     pub use super::delete_clause::*;
     pub use super::get_clause::*;
+    pub use super::include_statement::*;
+    pub use super::insert_clause::*;
+    pub use super::let_statement::*;
+    pub use super::move_clause::*;
     pub use super::mutation::*;
+    pub use super::predicate_definition::*;
+    pub use super::rename_clause::*;
+    pub use super::select_clause::*;
     pub use super::set_clause::*;
+    pub use super::sort_clause::*;
     pub use super::statement::*;
+    pub use super::validate::*;
     pub use super::where_clause::*;
+    pub use super::wrap_clause::*;
 }
@@ -0,0 +1,51 @@
+use crate::prelude::*;
+use nom::{
+    branch::alt, bytes::complete::tag_no_case, character::complete::multispace1, combinator::opt,
+    sequence::preceded, IResult, Parser,
+};
+use xml_mut_data::{InsertPosition, MoveClause};
+
+/// `path [FIRST|LAST|BEFORE|AFTER]`, last child of the node by default
+pub fn move_position(s: &str) -> IResult<&str, InsertPosition<'_>> {
+    let (s, path) = position_path(s)?;
+    let (s, word) = opt(preceded(
+        multispace1,
+        alt((
+            tag_no_case("first"),
+            tag_no_case("last"),
+            tag_no_case("before"),
+            tag_no_case("after"),
+        )),
+    ))
+    .parse(s)?;
+
+    let position = match word.map(str::to_ascii_lowercase).as_deref() {
+        Some("first") => InsertPosition::First(path),
+        Some("before") => InsertPosition::Before(path),
+        Some("after") => InsertPosition::After(path),
+        _ => InsertPosition::Last(path),
+    };
+    Ok((s, position))
+}
+
+pub fn move_clause(s: &str) -> IResult<&str, MoveClause<'_>> {
+    let (s, move_word) = alt((tag_no_case("move"), tag_no_case("copy"))).parse(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, source) = position_path(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("to")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, position) = move_position(s)?;
+    let (s, where_clause) = opt(preceded(multispace1, where_clause)).parse(s)?;
+
+    Ok((
+        s,
+        MoveClause {
+            move_word,
+            copy: move_word.eq_ignore_ascii_case("copy"),
+            source,
+            position,
+            where_clause,
+        },
+    ))
+}
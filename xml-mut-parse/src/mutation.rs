@@ -1,15 +1,54 @@
 use crate::prelude::*;
-use nom::{character::complete::multispace1, combinator::opt, sequence::preceded, IResult, Parser};
+use nom::{
+    bytes::complete::{tag, tag_no_case, take_while1},
+    character::complete::{multispace0, multispace1},
+    combinator::opt,
+    sequence::preceded,
+    IResult, Parser,
+};
 use xml_mut_data::Mutation;
 
-pub fn mutation(s: &str) -> IResult<&str, Mutation> {
+pub fn is_valid_in_statement_name(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+pub fn mutation_name(s: &str) -> IResult<&str, &str> {
+    let (s, _) = tag_no_case("mutation")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, name) = take_while1(is_valid_in_statement_name)(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = tag(":")(s)?;
+    let (s, _) = multispace1(s)?;
+    Ok((s, name))
+}
+
+pub fn mutation(s: &str) -> IResult<&str, Mutation<'_>> {
+    let (s, name) = opt(mutation_name).parse(s)?;
     let (s, get_clause) = get_clause(s)?;
     let (s, where_clause) = opt(preceded(multispace1, where_clause)).parse(s)?;
     let mem = s;
+    let (s, select_clause) = opt(preceded(multispace1, select_clause)).parse(s)?;
     let (s, set_clause) = opt(preceded(multispace1, set_clause)).parse(s)?;
+    let (s, rename_clause) = opt(preceded(multispace1, rename_clause)).parse(s)?;
+    let (s, insert_clause) = opt(preceded(multispace1, insert_clause)).parse(s)?;
+    let (s, move_clause) = opt(preceded(multispace1, move_clause)).parse(s)?;
+    let (s, wrap_clause) = opt(preceded(multispace1, wrap_clause)).parse(s)?;
+    let (s, unwrap_clause) = opt(preceded(multispace1, unwrap_clause)).parse(s)?;
+    let (s, sort_clause) = opt(preceded(multispace1, sort_clause)).parse(s)?;
+    let (s, deduplicate_clause) = opt(preceded(multispace1, deduplicate_clause)).parse(s)?;
     let (s, delete_clause) = opt(preceded(multispace1, delete_clause)).parse(s)?;
 
-    if set_clause.is_none() && delete_clause.is_none() {
+    if select_clause.is_none()
+        && set_clause.is_none()
+        && rename_clause.is_none()
+        && insert_clause.is_none()
+        && move_clause.is_none()
+        && wrap_clause.is_none()
+        && unwrap_clause.is_none()
+        && sort_clause.is_none()
+        && deduplicate_clause.is_none()
+        && delete_clause.is_none()
+    {
         return Err(nom::Err::Error(nom::error::Error {
             code: nom::error::ErrorKind::Permutation,
             input: mem,
@@ -19,9 +58,18 @@ pub fn mutation(s: &str) -> IResult<&str, Mutation> {
     Ok((
         s,
         Mutation {
+            name,
             get_clause,
             where_clause,
+            select_clause,
             set_clause,
+            rename_clause,
+            insert_clause,
+            move_clause,
+            wrap_clause,
+            unwrap_clause,
+            sort_clause,
+            deduplicate_clause,
             delete_clause,
         },
     ))
@@ -0,0 +1,32 @@
+use crate::{
+    mutation::is_valid_in_statement_name,
+    where_clause::{and_surounded_mulispace1, predicate},
+};
+use nom::{
+    bytes::complete::{tag_no_case, take_while1},
+    character::complete::multispace1,
+    multi::separated_list1,
+    IResult, Parser,
+};
+use xml_mut_data::PredicateDefinition;
+
+pub fn predicate_definition(s: &str) -> IResult<&str, PredicateDefinition<'_>> {
+    let (s, define_word) = tag_no_case("define")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("predicate")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, name) = take_while1(is_valid_in_statement_name)(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("as")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, predicates) = separated_list1(and_surounded_mulispace1, predicate).parse(s)?;
+
+    Ok((
+        s,
+        PredicateDefinition {
+            define_word,
+            name,
+            predicates,
+        },
+    ))
+}
@@ -0,0 +1,69 @@
+use crate::{get_clause::is_valid_in_xml_node_name, prelude::*};
+use nom::{
+    bytes::complete::{tag_no_case, take_while1},
+    character::complete::multispace1,
+    combinator::opt,
+    multi::separated_list1,
+    IResult, Parser,
+};
+use xml_mut_data::{PathVariant, Rename, RenameClause, ValueSelector};
+
+fn to_word(s: &str) -> IResult<&str, &str> {
+    let (s, _) = multispace1(s)?;
+    let (s, to_word) = tag_no_case("to")(s)?;
+    let (s, _) = multispace1(s)?;
+    Ok((s, to_word))
+}
+
+/// `path[@Old] TO [@New]` or `path TO NewName`, `.` is the matched node
+pub fn rename(s: &str) -> IResult<&str, Rename<'_>> {
+    let (s, attribute) = opt(value_path).parse(s)?;
+    if let Some(attribute) = attribute {
+        if !matches!(attribute.selector, ValueSelector::Attribute(_)) {
+            return Err(nom::Err::Error(nom::error::Error {
+                code: nom::error::ErrorKind::Tag,
+                input: s,
+            }));
+        }
+        let (s, _) = to_word(s)?;
+        let (rest, name) = value_source(s)?;
+        return match name {
+            ValueSelector::Attribute(name) => Ok((
+                rest,
+                Rename {
+                    source: PathVariant::Value(attribute),
+                    name,
+                },
+            )),
+            _ => Err(nom::Err::Error(nom::error::Error {
+                code: nom::error::ErrorKind::Tag,
+                input: s,
+            })),
+        };
+    }
+
+    let (s, path) = position_path(s)?;
+    let (s, _) = to_word(s)?;
+    let (s, name) = take_while1(is_valid_in_xml_node_name)(s)?;
+    Ok((
+        s,
+        Rename {
+            source: PathVariant::Node(path),
+            name,
+        },
+    ))
+}
+
+pub fn rename_clause(s: &str) -> IResult<&str, RenameClause<'_>> {
+    let (s, rename_word) = tag_no_case("rename")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, renames) = separated_list1(comma_surounded_mulispace01, rename).parse(s)?;
+
+    Ok((
+        s,
+        RenameClause {
+            rename_word,
+            renames,
+        },
+    ))
+}
@@ -0,0 +1,20 @@
+use crate::{prelude::comma_surounded_mulispace01, where_clause::value_path};
+use nom::{
+    bytes::complete::tag_no_case, character::complete::multispace1, multi::separated_list1,
+    IResult, Parser,
+};
+use xml_mut_data::SelectClause;
+
+pub fn select_clause(s: &str) -> IResult<&str, SelectClause<'_>> {
+    let (s, select_word) = tag_no_case("select")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, values) = separated_list1(comma_surounded_mulispace01, value_path).parse(s)?;
+
+    Ok((
+        s,
+        SelectClause {
+            select_word,
+            values,
+        },
+    ))
+}
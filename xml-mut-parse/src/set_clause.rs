@@ -1,10 +1,10 @@
-use crate::where_clause::value_path;
+use crate::{mutation::is_valid_in_statement_name, where_clause::value_path};
 use nom::{
-    bytes::complete::{tag_no_case, take_till},
+    bytes::complete::{tag, tag_no_case, take_till, take_while1},
     character::complete::{char, multispace0, multispace1},
     combinator::opt,
     multi::separated_list1,
-    sequence::delimited,
+    sequence::{delimited, preceded},
     IResult, Parser,
 };
 use xml_mut_data::{SetClause, ValueAssignment, ValueVariant};
@@ -14,17 +14,35 @@ pub fn literal_quoted_string(s: &str) -> IResult<&str, &str> {
     Ok((s, res))
 }
 
-pub fn value_variant(s: &str) -> IResult<&str, ValueVariant> {
+/// `xml"<a b='1'/>"`, the markup is returned without the quotes
+pub fn literal_xml(s: &str) -> IResult<&str, &str> {
+    preceded(tag_no_case("xml"), literal_quoted_string).parse(s)
+}
+
+/// A `$name` parameter, the name is returned without the `$`
+pub fn parameter(s: &str) -> IResult<&str, &str> {
+    preceded(tag("$"), take_while1(is_valid_in_statement_name)).parse(s)
+}
+
+pub fn value_variant(s: &str) -> IResult<&str, ValueVariant<'_>> {
     let (s, maybe_p_node_exists) = opt(value_path).parse(s)?;
-    Ok(if let Some(p_node_exists) = maybe_p_node_exists {
-        (s, ValueVariant::Selector(p_node_exists))
+    if let Some(p_node_exists) = maybe_p_node_exists {
+        return Ok((s, ValueVariant::Selector(p_node_exists)));
+    }
+    let (s, maybe_parameter) = opt(parameter).parse(s)?;
+    if let Some(name) = maybe_parameter {
+        return Ok((s, ValueVariant::Parameter(name)));
+    }
+    let (s, maybe_xml) = opt(literal_xml).parse(s)?;
+    Ok(if let Some(xml) = maybe_xml {
+        (s, ValueVariant::Xml(xml))
     } else {
         let (s, p_equals) = literal_quoted_string(s)?;
         (s, ValueVariant::LiteralString(p_equals))
     })
 }
 
-pub fn value_assignment(s: &str) -> IResult<&str, ValueAssignment> {
+pub fn value_assignment(s: &str) -> IResult<&str, ValueAssignment<'_>> {
     let (s, target) = value_path(s)?;
     let (s, _) = multispace1(s)?;
     let (s, _) = tag_no_case("=")(s)?;
@@ -41,7 +59,7 @@ pub fn comma_surounded_mulispace01(s: &str) -> IResult<&str, &str> {
     Ok((s, and_word))
 }
 
-pub fn set_clause(s: &str) -> IResult<&str, SetClause> {
+pub fn set_clause(s: &str) -> IResult<&str, SetClause<'_>> {
     let (s, set_word) = tag_no_case("set")(s)?;
     let (s, _) = multispace1(s)?;
     let (s, assignments) =
@@ -0,0 +1,44 @@
+use crate::prelude::*;
+use nom::{
+    branch::alt, bytes::complete::tag_no_case, character::complete::multispace1, combinator::opt,
+    sequence::preceded, IResult, Parser,
+};
+use xml_mut_data::SortClause;
+
+/// `ASC` or `DESC`, true for descending
+fn sort_order(s: &str) -> IResult<&str, bool> {
+    alt((
+        tag_no_case("asc").map(|_| false),
+        tag_no_case("desc").map(|_| true),
+    ))
+    .parse(s)
+}
+
+fn case_insensitive(s: &str) -> IResult<&str, &str> {
+    let (s, _) = tag_no_case("case")(s)?;
+    let (s, _) = multispace1(s)?;
+    tag_no_case("insensitive")(s)
+}
+
+pub fn sort_clause(s: &str) -> IResult<&str, SortClause<'_>> {
+    let (s, sort_word) = tag_no_case("sort")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, path) = node_path(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("by")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, key) = value_path(s)?;
+    let (s, descending) = opt(preceded(multispace1, sort_order)).parse(s)?;
+    let (s, insensitive) = opt(preceded(multispace1, case_insensitive)).parse(s)?;
+
+    Ok((
+        s,
+        SortClause {
+            sort_word,
+            path,
+            key,
+            descending: descending.unwrap_or(false),
+            case_insensitive: insensitive.is_some(),
+        },
+    ))
+}
@@ -4,7 +4,7 @@ use nom::{
     character::complete::{multispace0, multispace1},
     combinator::opt,
     multi::separated_list1,
-    sequence::delimited,
+    sequence::{delimited, preceded},
     IResult, Parser,
 };
 use xml_mut_data::{Statement, XmlMutGrammar};
@@ -14,18 +14,47 @@ fn block_comment(s: &str) -> IResult<&str, &str> {
     Ok((s, comment))
 }
 
+/// Name from an annotation comment like `/* @name bump_json */`
+fn name_annotation(comment: &str) -> Option<&str> {
+    let name = comment.trim().strip_prefix("@name")?;
+    if !name.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let name = name.trim();
+    (!name.is_empty() && name.chars().all(is_valid_in_statement_name)).then_some(name)
+}
+
 // TODO: impl line comment
 
-pub fn statement(s: &str) -> IResult<&str, Statement> {
+pub fn statement(s: &str) -> IResult<&str, Statement<'_>> {
     let (s, comment) = opt(block_comment).parse(s)?;
     if let Some(comment) = comment {
+        // an annotation names the mutation right after it
+        if let Some(name) = name_annotation(comment) {
+            if let (s, Some(mut res)) = opt(preceded(multispace0, mutation)).parse(s)? {
+                res.name = res.name.or(Some(name));
+                return Ok((s, Statement::Mutation(Box::new(res))));
+            }
+        }
         return Ok((s, Statement::Comment(comment)));
     }
+    let (s, include) = opt(include_statement).parse(s)?;
+    if let Some(include) = include {
+        return Ok((s, Statement::Include(include)));
+    }
+    let (s, let_res) = opt(let_statement).parse(s)?;
+    if let Some(let_res) = let_res {
+        return Ok((s, Statement::Let(let_res)));
+    }
+    let (s, definition) = opt(predicate_definition).parse(s)?;
+    if let Some(definition) = definition {
+        return Ok((s, Statement::DefinePredicate(definition)));
+    }
     let (s, res) = mutation(s)?;
-    Ok((s, Statement::Mutation(res)))
+    Ok((s, Statement::Mutation(Box::new(res))))
 }
 
-pub fn xml_mut_grammar(s: &str) -> IResult<&str, XmlMutGrammar> {
+pub fn xml_mut_grammar(s: &str) -> IResult<&str, XmlMutGrammar<'_>> {
     let (s, _) = multispace0(s)?;
     let (s, statements) = separated_list1(multispace1, statement).parse(s)?;
     let (s, _) = multispace0(s)?;
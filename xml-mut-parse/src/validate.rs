@@ -0,0 +1,134 @@
+use std::fmt;
+use xml_mut_data::{Bindings, Predicate, Statement, XmlMutGrammar};
+
+/// Position of parsed text in the mutation source, both starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Location of `fragment` within `source`, parsers keep slices of the
+/// source text so the location is found from their offset.
+pub fn location(source: &str, fragment: &str) -> Option<Location> {
+    let offset = (fragment.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    // a fragment of another source may start right where this one ends
+    source.get(offset..offset + fragment.len())?;
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Some(Location {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    })
+}
+
+/// Location in `source` where parsing failed with `err`
+pub fn error_location(source: &str, err: &nom::Err<nom::error::Error<&str>>) -> Option<Location> {
+    match err {
+        nom::Err::Error(e) | nom::Err::Failure(e) => location(source, e.input),
+        nom::Err::Incomplete(_) => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnboundParameter<'a> {
+    pub name: &'a str,
+    /// Location of the `$` in front of the name
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for UnboundParameter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Parameter ${} is not bound", self.name)?;
+        if let Some(location) = self.location {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parameters used in the grammar without a value in `bindings`
+/// or a `LET` statement
+pub fn unbound_parameters<'a>(
+    source: &str,
+    grammar: &XmlMutGrammar<'a>,
+    bindings: &Bindings,
+) -> Vec<UnboundParameter<'a>> {
+    let is_let = |name: &str| {
+        grammar
+            .statements
+            .iter()
+            .any(|s| matches!(s, Statement::Let(l) if l.name == name))
+    };
+    grammar
+        .statements
+        .iter()
+        .flat_map(|s| match s {
+            Statement::Mutation(m) => m.parameters(),
+            Statement::DefinePredicate(d) => {
+                d.predicates.iter().filter_map(|p| p.parameter()).collect()
+            }
+            Statement::Comment(_) | Statement::Let(_) | Statement::Include(_) => vec![],
+        })
+        .filter(|name| bindings.get(name).is_none() && !is_let(name))
+        .map(|name| UnboundParameter {
+            name,
+            location: location(source, name).map(|l| Location {
+                column: l.column - 1,
+                ..l
+            }),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPredicate<'a> {
+    pub name: &'a str,
+    /// Location of the reference
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for UnknownPredicate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Predicate {} is not defined", self.name)?;
+        if let Some(location) = self.location {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Predicates referenced by name without a `DEFINE PREDICATE` statement
+pub fn unknown_predicates<'a>(
+    source: &str,
+    grammar: &XmlMutGrammar<'a>,
+) -> Vec<UnknownPredicate<'a>> {
+    let is_defined = |name: &str| {
+        grammar
+            .statements
+            .iter()
+            .any(|s| matches!(s, Statement::DefinePredicate(d) if d.name == name))
+    };
+    grammar
+        .statements
+        .iter()
+        .flat_map(|s| match s {
+            Statement::Mutation(m) => m.predicates().collect(),
+            Statement::DefinePredicate(d) => d.predicates.iter().collect(),
+            Statement::Comment(_) | Statement::Let(_) | Statement::Include(_) => vec![],
+        })
+        .filter_map(|p| match p {
+            Predicate::Reference(name) if !is_defined(name) => Some(UnknownPredicate {
+                name,
+                location: location(source, name),
+            }),
+            _ => None,
+        })
+        .collect()
+}
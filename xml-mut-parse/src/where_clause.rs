@@ -1,17 +1,19 @@
-use crate::{get_clause::node_path, set_clause::value_variant};
+use crate::{
+    get_clause::node_path, mutation::is_valid_in_statement_name, set_clause::value_variant,
+};
 use nom::{
-    bytes::complete::{tag, tag_no_case, take_till},
+    bytes::complete::{tag, tag_no_case, take_till, take_while1},
     character::complete::multispace1,
-    combinator::opt,
+    combinator::{opt, verify},
     multi::separated_list1,
     IResult, Parser,
 };
 use xml_mut_data::{
-    NodePath, PathVariant, Predicate, PredicateEquals, PredicateExists, ValuePath, ValueSelector,
-    WhereClause,
+    NodePath, PathVariant, Predicate, PredicateEquals, PredicateExists, PredicateStartsWith,
+    ValuePath, ValueSelector, WhereClause,
 };
 
-pub fn value_source(s: &str) -> IResult<&str, ValueSelector> {
+pub fn value_source(s: &str) -> IResult<&str, ValueSelector<'_>> {
     let (s, _) = tag("[")(s)?;
     let (s, at) = opt(tag("@")).parse(s)?;
     let (s, name) = take_till(|c: char| c == ']')(s)?;
@@ -22,8 +24,15 @@ pub fn value_source(s: &str) -> IResult<&str, ValueSelector> {
     } else {
         match name {
             "text" => (s, ValueSelector::Text),
+            "string" => (s, ValueSelector::String),
             "tail" => (s, ValueSelector::Tail),
             "name" => (s, ValueSelector::Name),
+            "inner" => (s, ValueSelector::Inner),
+            "outer" => (s, ValueSelector::Outer),
+            "comment" => (s, ValueSelector::Comment),
+            _ if name.len() > 3 && name.starts_with("pi:") => {
+                (s, ValueSelector::ProcessingInstruction(&name[3..]))
+            }
             _ => {
                 return Err(nom::Err::Error(nom::error::Error {
                     code: nom::error::ErrorKind::Tag,
@@ -34,7 +43,7 @@ pub fn value_source(s: &str) -> IResult<&str, ValueSelector> {
     })
 }
 
-pub fn value_path(s: &str) -> IResult<&str, ValuePath> {
+pub fn value_path(s: &str) -> IResult<&str, ValuePath<'_>> {
     let (s, node_path) = opt(node_path).parse(s)?;
     let (s, source) = value_source(s)?;
     Ok(if let Some(node_path) = node_path {
@@ -57,7 +66,7 @@ pub fn value_path(s: &str) -> IResult<&str, ValuePath> {
 }
 
 // TODO: non desttructive parse of node path or value selector
-pub fn path_variant(s: &str) -> IResult<&str, PathVariant> {
+pub fn path_variant(s: &str) -> IResult<&str, PathVariant<'_>> {
     let (s, value) = opt(value_path).parse(s)?;
     if let Some(value) = value {
         Ok((s, PathVariant::Value(value)))
@@ -67,7 +76,7 @@ pub fn path_variant(s: &str) -> IResult<&str, PathVariant> {
     }
 }
 
-pub fn predicate_node_exists(s: &str) -> IResult<&str, PredicateExists> {
+pub fn predicate_node_exists(s: &str) -> IResult<&str, PredicateExists<'_>> {
     let (s, exists_word) = tag_no_case("exists")(s)?;
     let (s, _) = multispace1(s)?;
     let (s, path) = path_variant(s)?;
@@ -75,7 +84,7 @@ pub fn predicate_node_exists(s: &str) -> IResult<&str, PredicateExists> {
     Ok((s, PredicateExists { exists_word, path }))
 }
 
-pub fn predicate_equals(s: &str) -> IResult<&str, PredicateEquals> {
+pub fn predicate_equals(s: &str) -> IResult<&str, PredicateEquals<'_>> {
     let (s, left_side) = value_path(s)?;
     let (s, _) = multispace1(s)?;
     let (s, _) = tag("==")(s)?;
@@ -91,18 +100,74 @@ pub fn predicate_equals(s: &str) -> IResult<&str, PredicateEquals> {
     ))
 }
 
-pub fn predicate(s: &str) -> IResult<&str, Predicate> {
-    let (s, maybe_p_node_exists) = opt(predicate_node_exists).parse(s)?;
+pub fn predicate_starts_with(s: &str) -> IResult<&str, PredicateStartsWith<'_>> {
+    let (s, left_side) = value_path(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("starts")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("with")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, right_side) = value_variant(s)?;
 
-    Ok(if let Some(p_node_exists) = maybe_p_node_exists {
-        (s, Predicate::Exists(p_node_exists))
-    } else {
-        let (s, p_equals) = predicate_equals(s)?;
-        (s, Predicate::Equals(p_equals))
+    Ok((
+        s,
+        PredicateStartsWith {
+            left_side,
+            right_side,
+        },
+    ))
+}
+
+/// Words that start a statement, a clause or a predicate
+const KEYWORDS: &[&str] = &[
+    "get",
+    "where",
+    "and",
+    "exists",
+    "select",
+    "set",
+    "rename",
+    "insert",
+    "move",
+    "copy",
+    "wrap",
+    "unwrap",
+    "sort",
+    "deduplicate",
+    "delete",
+    "define",
+    "let",
+    "include",
+    "mutation",
+];
+
+/// Name of a predicate defined with `DEFINE PREDICATE`, keywords are not
+/// names
+pub fn predicate_reference(s: &str) -> IResult<&str, &str> {
+    verify(take_while1(is_valid_in_statement_name), |name: &str| {
+        !KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(name))
     })
+    .parse(s)
+}
+
+pub fn predicate(s: &str) -> IResult<&str, Predicate<'_>> {
+    let (s, maybe_p_node_exists) = opt(predicate_node_exists).parse(s)?;
+    if let Some(p_node_exists) = maybe_p_node_exists {
+        return Ok((s, Predicate::Exists(p_node_exists)));
+    }
+    let (s, maybe_p_equals) = opt(predicate_equals).parse(s)?;
+    if let Some(p_equals) = maybe_p_equals {
+        return Ok((s, Predicate::Equals(p_equals)));
+    }
+    let (s, maybe_p_starts_with) = opt(predicate_starts_with).parse(s)?;
+    if let Some(p_starts_with) = maybe_p_starts_with {
+        return Ok((s, Predicate::StartsWith(p_starts_with)));
+    }
+    let (s, name) = predicate_reference(s)?;
+    Ok((s, Predicate::Reference(name)))
 }
 
-fn and_surounded_mulispace1(s: &str) -> IResult<&str, &str> {
+pub fn and_surounded_mulispace1(s: &str) -> IResult<&str, &str> {
     let (s, _) = multispace1(s)?;
     let (s, and_word) = tag_no_case("and")(s)?;
     let (s, _) = multispace1(s)?;
@@ -110,7 +175,7 @@ fn and_surounded_mulispace1(s: &str) -> IResult<&str, &str> {
     Ok((s, and_word))
 }
 
-pub fn where_clause(s: &str) -> IResult<&str, WhereClause> {
+pub fn where_clause(s: &str) -> IResult<&str, WhereClause<'_>> {
     let (s, where_word) = tag_no_case("where")(s)?;
     let (s, _) = multispace1(s)?;
     let (s, predicates) = separated_list1(and_surounded_mulispace1, predicate).parse(s)?;
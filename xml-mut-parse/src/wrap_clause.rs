@@ -0,0 +1,49 @@
+use crate::{get_clause::is_valid_in_xml_node_name, insert_clause::element_attribute};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, tag_no_case, take_while1},
+    character::complete::{multispace0, multispace1},
+    multi::{many0, many1},
+    sequence::preceded,
+    IResult, Parser,
+};
+use xml_mut_data::{ElementTemplate, UnwrapClause, WrapClause};
+
+/// `<Name attr="literal" other=[@Source]>`, an element without content
+pub fn wrapper_template(s: &str) -> IResult<&str, ElementTemplate<'_>> {
+    let (s, _) = tag("<")(s)?;
+    let (s, name) = take_while1(is_valid_in_xml_node_name)(s)?;
+    let (s, attributes) = many0(preceded(multispace1, element_attribute)).parse(s)?;
+    let (s, _) = multispace0(s)?;
+    let (s, _) = alt((tag(">"), tag("/>"))).parse(s)?;
+
+    Ok((
+        s,
+        ElementTemplate {
+            name,
+            attributes,
+            text: None,
+        },
+    ))
+}
+
+pub fn wrap_clause(s: &str) -> IResult<&str, WrapClause<'_>> {
+    let (s, wrap_word) = tag_no_case("wrap")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, _) = tag_no_case("in")(s)?;
+    let (s, _) = multispace1(s)?;
+    let (s, wrappers) = many1(preceded(multispace0, wrapper_template)).parse(s)?;
+
+    Ok((
+        s,
+        WrapClause {
+            wrap_word,
+            wrappers,
+        },
+    ))
+}
+
+pub fn unwrap_clause(s: &str) -> IResult<&str, UnwrapClause<'_>> {
+    let (s, unwrap_word) = tag_no_case("unwrap")(s)?;
+    Ok((s, UnwrapClause { unwrap_word }))
+}
@@ -0,0 +1,48 @@
+use xml_mut_data::{DeduplicateKeep, NodePath, ValuePath, ValueSelector};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_deduplicate_clause() {
+    let (s, c) = deduplicate_clause("DEDUPLICATE PackageReference BY [@Include]")
+        .expect("could not parse deduplicate");
+    assert!(s.is_empty());
+    assert_eq!(c.path.path, vec!["PackageReference"]);
+    assert_eq!(
+        c.key,
+        ValuePath {
+            node_path: NodePath { path: vec![] },
+            selector: ValueSelector::Attribute("Include"),
+        }
+    );
+    assert_eq!(c.keep, DeduplicateKeep::First);
+}
+
+#[test]
+fn parse_deduplicate_clause_keep() {
+    let (s, c) = deduplicate_clause("deduplicate ItemGroup/Compile by [@Include] keep last")
+        .expect("could not parse deduplicate");
+    assert!(s.is_empty());
+    assert_eq!(c.path.path, vec!["ItemGroup", "Compile"]);
+    assert_eq!(c.keep, DeduplicateKeep::Last);
+
+    let fragment = "DEDUPLICATE PackageReference BY [@Include] KEEP HIGHEST Version[text]";
+    let (s, c) = deduplicate_clause(fragment).expect("could not parse deduplicate");
+    assert!(s.is_empty());
+    assert_eq!(
+        c.keep,
+        DeduplicateKeep::Highest(ValuePath {
+            node_path: NodePath {
+                path: vec!["Version"]
+            },
+            selector: ValueSelector::Text,
+        })
+    );
+}
+
+#[test]
+fn parse_deduplicate_clause_highest_needs_a_value() {
+    let (s, c) = deduplicate_clause("DEDUPLICATE PackageReference BY [@Include] KEEP HIGHEST")
+        .expect("could not parse deduplicate");
+    assert_eq!(s, " KEEP HIGHEST");
+    assert_eq!(c.keep, DeduplicateKeep::First);
+}
@@ -1,4 +1,6 @@
-use xml_mut_data::{NodePath, PathVariant, ValuePath, ValueSelector};
+use xml_mut_data::{
+    NodePath, PathVariant, Predicate, PredicateEquals, ValuePath, ValueSelector, ValueVariant,
+};
 use xml_mut_parse::prelude::*;
 
 #[test]
@@ -9,7 +11,7 @@ fn parse_delete_clause_01() {
     assert_eq!(b.targets.len(), 1);
 
     assert_eq!(
-        b.targets[0],
+        b.targets[0].path,
         PathVariant::Node(NodePath {
             path: vec!["ItemGroup", "PackageRef", "fomo"]
         })
@@ -24,14 +26,14 @@ fn parse_delete_clause_02() {
     assert_eq!(b.targets.len(), 2);
 
     assert_eq!(
-        b.targets[0],
+        b.targets[0].path,
         PathVariant::Node(NodePath {
             path: vec!["ItemGroup", "PackageRef"]
         })
     );
 
     assert_eq!(
-        b.targets[1],
+        b.targets[1].path,
         PathVariant::Value(ValuePath {
             node_path: NodePath {
                 path: vec!["ItemGroup"]
@@ -40,3 +42,50 @@ fn parse_delete_clause_02() {
         })
     );
 }
+
+#[test]
+fn parse_delete_clause_empty() {
+    let fragment = "DELETE EMPTY ItemGroup, EMPTY PropertyGroup";
+    let (s, b) = delete_clause(fragment).expect("could not parse delete statement");
+    assert!(s.is_empty());
+    assert_eq!(b.targets.len(), 2);
+    assert!(b.targets[0].empty);
+    assert_eq!(
+        b.targets[1].path,
+        PathVariant::Node(NodePath {
+            path: vec!["PropertyGroup"]
+        })
+    );
+    assert!(b.targets[1].is_conditional());
+
+    // a name that starts with the word is still a name
+    let (_, b) = delete_clause("delete EmptyGroup").expect("could not parse delete statement");
+    assert!(!b.targets[0].empty);
+}
+
+#[test]
+fn parse_delete_clause_where() {
+    let fragment = "DELETE Version WHERE Version[text] == \"\", EMPTY ItemGroup, [@Old]";
+    let (s, b) = delete_clause(fragment).expect("could not parse delete statement");
+    assert!(s.is_empty());
+    assert_eq!(b.targets.len(), 3);
+    let where_clause = b.targets[0]
+        .where_clause
+        .as_ref()
+        .expect("first target should have a where clause");
+    assert_eq!(
+        where_clause.predicates,
+        vec![Predicate::Equals(PredicateEquals {
+            left_side: ValuePath {
+                node_path: NodePath {
+                    path: vec!["Version"]
+                },
+                selector: ValueSelector::Text,
+            },
+            right_side: ValueVariant::LiteralString(""),
+        })]
+    );
+    assert!(!b.targets[0].empty);
+    assert!(b.targets[1].empty);
+    assert!(!b.targets[2].is_conditional());
+}
@@ -0,0 +1,20 @@
+use xml_mut_data::Statement;
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_include_statement() {
+    let fragment = "INCLUDE \"common/dotnet.xmlmut\"";
+    let (s, i) = include_statement(fragment).expect("could not parse include statement");
+    assert!(s.is_empty());
+    assert_eq!(i.include_word, "INCLUDE");
+    assert_eq!(i.path, "common/dotnet.xmlmut");
+}
+
+#[test]
+fn parse_grammar_with_include() {
+    let fragment = "include \"common/dotnet.xmlmut\"\nGET ItemGroup/Compile\nDELETE Link";
+    let (s, grammar) = xml_mut_grammar(fragment).expect("could not parse grammar");
+    assert!(s.is_empty());
+    assert!(matches!(grammar.statements[0], Statement::Include(_)));
+    assert!(matches!(grammar.statements[1], Statement::Mutation(_)));
+}
@@ -0,0 +1,87 @@
+use xml_mut_data::{InsertPosition, NodePath, ValuePath, ValueSelector, ValueVariant};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_element_template() {
+    let fragment = "<PackageReference Include=\"X\" Version=$version Label = [@Include]/>";
+    let (s, e) = element_template(fragment).expect("could not parse element template");
+    assert!(s.is_empty());
+    assert_eq!(e.name, "PackageReference");
+    assert_eq!(
+        e.attributes,
+        vec![
+            ("Include", ValueVariant::LiteralString("X")),
+            ("Version", ValueVariant::Parameter("version")),
+            (
+                "Label",
+                ValueVariant::Selector(ValuePath {
+                    node_path: NodePath { path: vec![] },
+                    selector: ValueSelector::Attribute("Include"),
+                })
+            ),
+        ]
+    );
+    assert_eq!(e.text, None);
+}
+
+#[test]
+fn parse_element_template_text() {
+    let fragment = "<Version>Version[text]</Version>";
+    let (s, e) = element_template(fragment).expect("could not parse element template");
+    assert!(s.is_empty());
+    assert!(e.attributes.is_empty());
+    assert_eq!(
+        e.text,
+        Some(ValueVariant::Selector(ValuePath {
+            node_path: NodePath {
+                path: vec!["Version"]
+            },
+            selector: ValueSelector::Text,
+        }))
+    );
+    assert!(element_template("<Version>\"1\"</Other>").is_err());
+}
+
+#[test]
+fn parse_insert_clause_positions() {
+    let position = |fragment| {
+        let (s, i) = insert_clause(fragment).expect("could not parse insert clause");
+        assert!(s.is_empty());
+        i.position
+    };
+    let path = |path| NodePath { path };
+
+    assert_eq!(
+        position("INSERT <A/> INTO ItemGroup AS LAST"),
+        InsertPosition::Last(path(vec!["ItemGroup"]))
+    );
+    assert_eq!(
+        position("insert <A/> into ItemGroup as first"),
+        InsertPosition::First(path(vec!["ItemGroup"]))
+    );
+    assert_eq!(
+        position("INSERT <A/> AS FIRST"),
+        InsertPosition::First(path(vec![]))
+    );
+    assert_eq!(position("INSERT <A/>"), InsertPosition::Last(path(vec![])));
+    assert_eq!(
+        position("INSERT <A/> BEFORE ItemGroup/Compile"),
+        InsertPosition::Before(path(vec!["ItemGroup", "Compile"]))
+    );
+    assert_eq!(
+        position("INSERT <A/> AFTER ."),
+        InsertPosition::After(path(vec![]))
+    );
+}
+
+#[test]
+fn parse_mutation_with_insert() {
+    let fragment =
+        "GET Project/ItemGroup\nINSERT <PackageReference Include=$package/>\nDELETE Compile";
+    let (s, m) = mutation(fragment).expect("could not parse mutation");
+    assert!(s.is_empty());
+    assert!(m.insert_clause.is_some());
+    assert!(m.delete_clause.is_some());
+    assert!(!m.is_query());
+    assert_eq!(m.parameters(), vec!["package"]);
+}
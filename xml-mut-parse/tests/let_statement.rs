@@ -0,0 +1,17 @@
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_let_statement() {
+    let fragment = "LET $version = \"8.0.0\"";
+    let (s, l) = let_statement(fragment).expect("could not parse let statement");
+    assert!(s.is_empty());
+    assert_eq!(l.let_word, "LET");
+    assert_eq!(l.name, "version");
+    assert_eq!(l.value, "8.0.0");
+}
+
+#[test]
+fn parse_let_statement_needs_literal() {
+    let fragment = "let $version = [@Version]";
+    assert!(let_statement(fragment).is_err());
+}
@@ -0,0 +1,54 @@
+use xml_mut_data::{InsertPosition, NodePath};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_move_clause() {
+    let fragment = "MOVE ItemGroup/None TO ItemGroup";
+    let (s, m) = move_clause(fragment).expect("could not parse move clause");
+    assert!(s.is_empty());
+    assert!(!m.copy);
+    assert_eq!(m.source.path, vec!["ItemGroup", "None"]);
+    assert_eq!(
+        m.position,
+        InsertPosition::Last(NodePath {
+            path: vec!["ItemGroup"]
+        })
+    );
+}
+
+#[test]
+fn parse_copy_clause_positions() {
+    let position = |fragment| {
+        let (s, m) = move_clause(fragment).expect("could not parse copy clause");
+        assert!(s.is_empty());
+        assert!(m.copy);
+        m.position
+    };
+    let path = |path| NodePath { path };
+    assert_eq!(
+        position("copy Version TO . first"),
+        InsertPosition::First(path(vec![]))
+    );
+    assert_eq!(
+        position("COPY . TO Next LAST"),
+        InsertPosition::Last(path(vec!["Next"]))
+    );
+    assert_eq!(
+        position("COPY Version TO Next BEFORE"),
+        InsertPosition::Before(path(vec!["Next"]))
+    );
+    assert_eq!(
+        position("COPY Version TO Next AFTER"),
+        InsertPosition::After(path(vec!["Next"]))
+    );
+    assert!(move_clause("COPY Version Next").is_err());
+}
+
+#[test]
+fn parse_move_clause_where() {
+    let fragment = r#"MOVE ItemGroup/None TO ItemGroup LAST WHERE ItemGroup[@Label] == "files""#;
+    let (s, m) = move_clause(fragment).expect("could not parse move clause");
+    assert!(s.is_empty());
+    let where_clause = m.where_clause.expect("should have a where clause");
+    assert_eq!(where_clause.predicates.len(), 1);
+}
@@ -62,6 +62,7 @@ SET Version[text] = [@Version]"###;
     assert_eq!(
         w,
         Mutation {
+            name: None,
             get_clause: GetClause {
                 get_word: "GET",
                 node_selector: NodePath {
@@ -69,6 +70,7 @@ SET Version[text] = [@Version]"###;
                 }
             },
             where_clause: None,
+            select_clause: None,
             set_clause: Some(SetClause {
                 set_word: "SET",
                 assignments: vec![ValueAssignment {
@@ -84,6 +86,13 @@ SET Version[text] = [@Version]"###;
                     })
                 }]
             }),
+            rename_clause: None,
+            insert_clause: None,
+            move_clause: None,
+            wrap_clause: None,
+            unwrap_clause: None,
+            sort_clause: None,
+            deduplicate_clause: None,
             delete_clause: None
         }
     );
@@ -93,6 +102,28 @@ SET Version[text] = [@Version]"###;
 fn parse_mutation_4() {
     let fragment = r###"GET ItemGroup/PackageReference
     WHERE Pempe[name] == "Pempe""###;
-    let result= mutation(fragment);
+    let result = mutation(fragment);
     assert!(result.is_err());
-}
\ No newline at end of file
+}
+
+#[test]
+fn parse_mutation_select() {
+    let fragment = r###"GET Project/ItemGroup/PackageReference
+    WHERE exists [@Version]
+    SELECT [@Include], [@Version]"###;
+    let (s, w) = mutation(fragment).expect("could not parse mutation");
+    assert!(s.is_empty());
+    assert!(w.is_query());
+    assert!(w.where_clause.is_some());
+    assert_eq!(w.select_clause.expect("select is parsed").values.len(), 2);
+}
+
+#[test]
+fn parse_mutation_named() {
+    let fragment = r###"MUTATION bump_json: GET ItemGroup/PackageReference
+    SET [@Version] = "8.0.0""###;
+    let (s, w) = mutation(fragment).expect("could not parse mutation");
+    assert!(s.is_empty());
+    assert_eq!(w.name, Some("bump_json"));
+    assert_eq!(w.get_clause.node_selector.path[1], "PackageReference");
+}
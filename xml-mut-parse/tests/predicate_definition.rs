@@ -0,0 +1,33 @@
+use xml_mut_data::{Predicate, Statement};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_predicate_definition() {
+    let fragment =
+        "DEFINE PREDICATE is_test AS [@Include] STARTS WITH \"xunit\" and exists Version";
+    let (s, d) = predicate_definition(fragment).expect("could not parse predicate definition");
+    assert!(s.is_empty());
+    assert_eq!(d.define_word, "DEFINE");
+    assert_eq!(d.name, "is_test");
+    assert_eq!(d.predicates.len(), 2);
+    assert!(matches!(d.predicates[0], Predicate::StartsWith(_)));
+}
+
+#[test]
+fn parse_grammar_with_definitions() {
+    let fragment = r###"LET $version = "8.0.0"
+DEFINE PREDICATE is_test AS [@Include] STARTS WITH "xunit"
+
+GET ItemGroup/PackageReference
+WHERE is_test
+SET [@Version] = $version"###;
+    let (s, grammar) = xml_mut_grammar(fragment).expect("could not parse grammar");
+    assert!(s.is_empty());
+    assert!(matches!(grammar.statements[0], Statement::Let(_)));
+    assert!(matches!(
+        grammar.statements[1],
+        Statement::DefinePredicate(_)
+    ));
+    assert!(matches!(grammar.statements[2], Statement::Mutation(_)));
+    assert!(unbound_parameters(fragment, &grammar, &Default::default()).is_empty());
+}
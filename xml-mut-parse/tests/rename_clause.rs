@@ -0,0 +1,45 @@
+use xml_mut_data::{NodePath, PathVariant, ValuePath, ValueSelector};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_rename_attribute() {
+    let (s, r) = rename("Child[@Old] TO [@New]").expect("could not parse rename");
+    assert!(s.is_empty());
+    assert_eq!(
+        r.source,
+        PathVariant::Value(ValuePath {
+            node_path: NodePath {
+                path: vec!["Child"]
+            },
+            selector: ValueSelector::Attribute("Old"),
+        })
+    );
+    assert_eq!(r.name, "New");
+    assert!(rename("[@Old] TO New").is_err());
+    assert!(rename("[text] TO [@New]").is_err());
+}
+
+#[test]
+fn parse_rename_element() {
+    let (s, r) = rename("Child TO NewChild").expect("could not parse rename");
+    assert!(s.is_empty());
+    assert_eq!(
+        r.source,
+        PathVariant::Node(NodePath {
+            path: vec!["Child"]
+        })
+    );
+    assert_eq!(r.name, "NewChild");
+}
+
+#[test]
+fn parse_rename_clause() {
+    let fragment = "RENAME [@Include] TO [@Update], . to Renamed";
+    let (s, r) = rename_clause(fragment).expect("could not parse rename clause");
+    assert!(s.is_empty());
+    assert_eq!(r.renames.len(), 2);
+    assert_eq!(
+        r.renames[1].source,
+        PathVariant::Node(NodePath { path: vec![] })
+    );
+}
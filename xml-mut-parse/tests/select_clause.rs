@@ -0,0 +1,24 @@
+use xml_mut_data::{NodePath, ValuePath, ValueSelector};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_select_clause_01() {
+    let fragment = "SELECT [@Include], Version[text]";
+    let (_, b) = select_clause(fragment).expect("could not parse select statement");
+    assert_eq!(b.select_word, "SELECT");
+    assert_eq!(
+        b.values,
+        vec![
+            ValuePath {
+                node_path: NodePath { path: vec![] },
+                selector: ValueSelector::Attribute("Include")
+            },
+            ValuePath {
+                node_path: NodePath {
+                    path: vec!["Version"]
+                },
+                selector: ValueSelector::Text
+            }
+        ]
+    );
+}
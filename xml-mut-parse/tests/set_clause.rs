@@ -29,6 +29,23 @@ fn parse_value_assignment_1() {
     assert_eq!(b.source, ValueVariant::LiteralString("true"));
 }
 
+#[test]
+fn parse_value_assignment_parameter() {
+    let fragment = "[@Version] = $version";
+    let (rem, b) = value_assignment(fragment).expect("could not parse value assignment");
+    assert!(rem.is_empty());
+    assert_eq!(b.source, ValueVariant::Parameter("version"));
+}
+
+#[test]
+fn parse_value_assignment_xml() {
+    let fragment = "Metadata[inner] = xml\"<x a='1'/>\"";
+    let (rem, b) = value_assignment(fragment).expect("could not parse value assignment");
+    assert!(rem.is_empty());
+    assert_eq!(b.target.selector, ValueSelector::Inner);
+    assert_eq!(b.source, ValueVariant::Xml("<x a='1'/>"));
+}
+
 #[test]
 fn parse_value_assignment_2() {
     let fragment = "r/tron[@morka] = r/balbon[text]";
@@ -0,0 +1,33 @@
+use xml_mut_data::{NodePath, ValuePath, ValueSelector};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_sort_clause() {
+    let (s, c) = sort_clause("SORT PackageReference BY [@Include]").expect("could not parse sort");
+    assert!(s.is_empty());
+    assert_eq!(c.path.path, vec!["PackageReference"]);
+    assert_eq!(
+        c.key,
+        ValuePath {
+            node_path: NodePath { path: vec![] },
+            selector: ValueSelector::Attribute("Include"),
+        }
+    );
+    assert!(!c.descending);
+    assert!(!c.case_insensitive);
+}
+
+#[test]
+fn parse_sort_clause_options() {
+    let fragment = "sort ItemGroup/Compile by Version[text] DESC case insensitive";
+    let (s, c) = sort_clause(fragment).expect("could not parse sort clause");
+    assert!(s.is_empty());
+    assert_eq!(c.path.path, vec!["ItemGroup", "Compile"]);
+    assert_eq!(c.key.selector, ValueSelector::Text);
+    assert!(c.descending);
+    assert!(c.case_insensitive);
+
+    let (s, c) = sort_clause("SORT Compile BY [@Include] ASC").expect("could not parse sort");
+    assert!(s.is_empty());
+    assert!(!c.descending);
+}
@@ -0,0 +1,77 @@
+use xml_mut_data::{Bindings, Statement};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_grammar_named_statements() {
+    let fragment = r###"/* bumps json */
+/* @name bump_json */
+GET ItemGroup/PackageReference
+SET [@Version] = "8.0.0"
+
+MUTATION drop_versions: GET ItemGroup/PackageReference
+DELETE Version
+
+GET ItemGroup/Compile
+DELETE Link"###;
+    let (s, grammar) = xml_mut_grammar(fragment).expect("could not parse grammar");
+    assert!(s.is_empty());
+
+    let names: Vec<Option<&str>> = grammar
+        .statements
+        .iter()
+        .filter_map(|s| match s {
+            Statement::Mutation(m) => Some(m.name),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, vec![Some("bump_json"), Some("drop_versions"), None]);
+    assert_eq!(grammar.statements[0], Statement::Comment(" bumps json "));
+}
+
+#[test]
+fn unbound_parameters_have_locations() {
+    let fragment =
+        "GET ItemGroup/PackageReference\nWHERE [@Include] == $package\nSET [@Version] = $version";
+    let (_, grammar) = xml_mut_grammar(fragment).expect("could not parse grammar");
+
+    let mut bindings = Bindings::default();
+    bindings.insert("package", "Mono.Cecil");
+    let unbound = unbound_parameters(fragment, &grammar, &bindings);
+    assert_eq!(
+        unbound,
+        vec![UnboundParameter {
+            name: "version",
+            location: Some(Location {
+                line: 3,
+                column: 18
+            })
+        }]
+    );
+    assert_eq!(
+        unbound[0].to_string(),
+        "Parameter $version is not bound at line 3, column 18"
+    );
+}
+
+#[test]
+fn unknown_predicates_have_locations() {
+    let fragment = "DEFINE PREDICATE is_test AS [@Include] STARTS WITH \"xunit\"\n\n\
+                    GET ItemGroup/PackageReference\nWHERE is_test and is_tset\nDELETE Version";
+    let (_, grammar) = xml_mut_grammar(fragment).expect("could not parse grammar");
+
+    let unknown = unknown_predicates(fragment, &grammar);
+    assert_eq!(
+        unknown,
+        vec![UnknownPredicate {
+            name: "is_tset",
+            location: Some(Location {
+                line: 4,
+                column: 19
+            })
+        }]
+    );
+    assert_eq!(
+        unknown[0].to_string(),
+        "Predicate is_tset is not defined at line 4, column 19"
+    );
+}
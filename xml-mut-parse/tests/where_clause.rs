@@ -43,6 +43,31 @@ fn parse_value_selector_ending_3() {
     assert_eq!(b, ValueSelector::Tail);
 }
 
+#[test]
+fn parse_value_selector_ending_inner_outer() {
+    let (_, inner) = value_source("[inner]").expect("could not parse inner value selector");
+    assert_eq!(inner, ValueSelector::Inner);
+    let (_, outer) = value_source("[outer]").expect("could not parse outer value selector");
+    assert_eq!(outer, ValueSelector::Outer);
+}
+
+#[test]
+fn parse_value_selector_comment_pi() {
+    let (_, comment) = value_source("[comment]").expect("could not parse comment value selector");
+    assert_eq!(comment, ValueSelector::Comment);
+    let (_, pi) = value_source("[pi:xml-stylesheet]").expect("could not parse pi value selector");
+    assert_eq!(pi, ValueSelector::ProcessingInstruction("xml-stylesheet"));
+    assert_eq!(pi.to_string(), "[pi:xml-stylesheet]");
+    assert!(value_source("[pi:]").is_err());
+}
+
+#[test]
+fn parse_value_selector_string() {
+    let (_, string) = value_source("[string]").expect("could not parse string value selector");
+    assert_eq!(string, ValueSelector::String);
+    assert_eq!(string.to_string(), "[string]");
+}
+
 #[test]
 fn parse_value_selector_ending_2() {
     let fragment = "[@version]";
@@ -124,3 +149,29 @@ fn parse_where_clause_2() {
     assert_eq!(w.where_word, "WhErE");
     assert_eq!(w.predicates.len(), 1);
 }
+
+#[test]
+fn parse_predicate_starts_with() {
+    let fragment = "[@Include] starts with \"xunit\"";
+    let (_, b) = predicate(fragment).expect("could not parse predicate starts with");
+    if let Predicate::StartsWith(p) = b {
+        assert_eq!(p.left_side.selector, ValueSelector::Attribute("Include"));
+        assert_eq!(p.right_side, ValueVariant::LiteralString("xunit"));
+    } else {
+        panic!("could not parse predicate starts with");
+    }
+}
+
+#[test]
+fn parse_predicate_reference() {
+    let fragment = "where is_test and exists Version";
+    let (_, w) = where_clause(fragment).expect("could not parse where clause");
+    assert_eq!(w.predicates[0], Predicate::Reference("is_test"));
+}
+
+#[test]
+fn keywords_are_not_predicate_references() {
+    assert!(where_clause("where SET [@Version] = \"1\"").is_err());
+    assert!(predicate("delete").is_err());
+    assert!(predicate("deleted").is_ok());
+}
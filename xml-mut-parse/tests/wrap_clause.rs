@@ -0,0 +1,41 @@
+use xml_mut_data::{NodePath, ValuePath, ValueSelector, ValueVariant};
+use xml_mut_parse::prelude::*;
+
+#[test]
+fn parse_wrap_clause() {
+    let fragment = "WRAP IN <Choose> <When Condition=[@Condition]>";
+    let (s, w) = wrap_clause(fragment).expect("could not parse wrap clause");
+    assert!(s.is_empty());
+    assert_eq!(w.wrappers.len(), 2);
+    assert_eq!(w.wrappers[0].name, "Choose");
+    assert!(w.wrappers[0].attributes.is_empty());
+    assert_eq!(w.wrappers[1].name, "When");
+    assert_eq!(
+        w.wrappers[1].attributes,
+        vec![(
+            "Condition",
+            ValueVariant::Selector(ValuePath {
+                node_path: NodePath { path: vec![] },
+                selector: ValueSelector::Attribute("Condition"),
+            })
+        )]
+    );
+}
+
+#[test]
+fn parse_wrap_clause_empty_element() {
+    let (s, w) = wrap_clause("wrap in <Group Label=\"x\"/>").expect("could not parse wrap clause");
+    assert!(s.is_empty());
+    assert_eq!(
+        w.wrappers[0].attributes,
+        vec![("Label", ValueVariant::LiteralString("x"))]
+    );
+    assert!(wrap_clause("WRAP <Group>").is_err());
+}
+
+#[test]
+fn parse_unwrap_clause() {
+    let (s, u) = unwrap_clause("UNWRAP").expect("could not parse unwrap clause");
+    assert!(s.is_empty());
+    assert_eq!(u.unwrap_word, "UNWRAP");
+}
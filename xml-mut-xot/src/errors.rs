@@ -10,6 +10,15 @@ pub enum Error {
     XotError(xot::Error),
     AssignmentSourceValueNotFound(String),
     NothingToAdd,
+    SpanNotFound,
+    OverlappingPatches,
+    PredicateNotFound(String),
+    PredicateCycle(String),
+    InsertTargetNotFound(String),
+    MoveIntoItself(String),
+    MoveTargetAmbiguous(String),
+    UnwrapDocumentElement,
+    InvalidNodeValue(String),
 }
 
 impl std::error::Error for Error {
@@ -39,6 +48,31 @@ impl fmt::Display for Error {
                 )
             }
             Error::NothingToAdd => write!(f, "There is nothing to add."),
+            Error::SpanNotFound => write!(f, "Failed to find node position in the source."),
+            Error::OverlappingPatches => {
+                write!(f, "Operations change overlapping parts of the source.")
+            }
+            Error::PredicateNotFound(name) => {
+                write!(f, "There is no predicate defined with name: {}", name)
+            }
+            Error::PredicateCycle(name) => {
+                write!(f, "Predicate {} is defined in terms of itself.", name)
+            }
+            Error::InsertTargetNotFound(path) => {
+                write!(f, "Failed to find node to insert at: {}", path)
+            }
+            Error::MoveIntoItself(path) => {
+                write!(f, "Failed to move a node into itself at: {}", path)
+            }
+            Error::MoveTargetAmbiguous(path) => {
+                write!(f, "More than one node to move to at: {}", path)
+            }
+            Error::UnwrapDocumentElement => {
+                write!(f, "The document element can not be unwrapped.")
+            }
+            Error::InvalidNodeValue(value) => {
+                write!(f, "The value can not be written as a node: {}", value)
+            }
         }
     }
 }
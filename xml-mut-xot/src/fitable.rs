@@ -3,59 +3,99 @@ use crate::{
     prelude::{NodeExtensions, Valueable},
 };
 use xml_mut_data::{
-    Mutation, PathVariant, Predicate, PredicateEquals, PredicateExists, ValueSelector,
+    Bindings, Mutation, PathVariant, Predicate, PredicateEquals, PredicateExists,
+    PredicateStartsWith, ValueSelector,
 };
 use xot::{Node, Xot};
 
 pub trait Fitable {
-    fn fits_predicates(&self, node: Node, predicates: &[Predicate]) -> bool {
-        predicates.iter().all(|p| self.fits_predicate(node, p))
+    fn fits_predicates(&self, node: Node, predicates: &[Predicate], bindings: &Bindings) -> bool {
+        predicates
+            .iter()
+            .all(|p| self.fits_predicate(node, p, bindings))
     }
-    fn fits_predicate(&self, node: Node, predicate: &Predicate) -> bool {
+    fn fits_predicate(&self, node: Node, predicate: &Predicate, bindings: &Bindings) -> bool {
         match predicate {
             Predicate::Exists(p) => self.fits_predicate_exists(node, p),
-            Predicate::Equals(p) => self.fits_predicate_equals(node, p),
+            Predicate::Equals(p) => self.fits_predicate_equals(node, p, bindings),
+            Predicate::StartsWith(p) => self.fits_predicate_starts_with(node, p, bindings),
+            // references are replaced by the predicates they name in `resolve`
+            Predicate::Reference(_) => false,
         }
     }
     fn fits_predicate_exists(&self, node: Node, predicate: &PredicateExists) -> bool;
-    fn fits_predicate_equals(&self, node: Node, predicate: &PredicateEquals) -> bool;
-    fn is_fit(&self, node: Node, mutation: &Mutation) -> bool;
+    fn fits_predicate_equals(
+        &self,
+        node: Node,
+        predicate: &PredicateEquals,
+        bindings: &Bindings,
+    ) -> bool;
+    fn fits_predicate_starts_with(
+        &self,
+        node: Node,
+        predicate: &PredicateStartsWith,
+        bindings: &Bindings,
+    ) -> bool;
+    fn is_fit(&self, node: Node, mutation: &Mutation, bindings: &Bindings) -> bool;
 }
 
 impl Fitable for Xot {
     fn fits_predicate_exists(&self, node: Node, predicate: &PredicateExists) -> bool {
-        let (path, source_maybe) = match &predicate.path {
-            PathVariant::Node(p) => (p, None),
-            PathVariant::Value(v) => (&v.node_path, Some(&v.selector)),
+        let (path, value_source) = match &predicate.path {
+            // any of the nodes at the path will do, not only the first one
+            PathVariant::Node(p) => return !self.find_child_elements(node, p).is_empty(),
+            PathVariant::Value(v) => (&v.node_path, &v.selector),
         };
 
         let node = ok_or_return_false!(self.find_first_child_element(node, path));
-        let value_source = ok_or_return_false!(source_maybe);
 
         match value_source {
             ValueSelector::Attribute(name) => {
                 let name_id = ok_or_return_false!(self.name(name));
                 self.attributes(node).contains_key(name_id)
             }
-            ValueSelector::Text => {
-                let child = ok_or_return_false!(self.first_child(node));
-                self.is_text(child)
+            // exists whenever it can be compared, an empty element has ""
+            ValueSelector::Text | ValueSelector::String => {
+                self.get_value(node, value_source).is_some()
             }
             ValueSelector::Tail => {
                 let sibling = ok_or_return_false!(self.next_sibling(node));
                 self.is_text(sibling)
             }
-            ValueSelector::Name => true,
+            ValueSelector::Name | ValueSelector::Outer => true,
+            ValueSelector::Inner => self.first_child(node).is_some(),
+            ValueSelector::Comment => self.comment_node(node).is_some(),
+            ValueSelector::ProcessingInstruction(target) => {
+                self.processing_instruction_node(node, target).is_some()
+            }
         }
     }
 
-    fn fits_predicate_equals(&self, node: Node, predicate: &PredicateEquals) -> bool {
-        let right_side_value = ok_or_return_false!(self.get_value_of(node, &predicate.right_side));
+    fn fits_predicate_equals(
+        &self,
+        node: Node,
+        predicate: &PredicateEquals,
+        bindings: &Bindings,
+    ) -> bool {
+        let right_side_value =
+            ok_or_return_false!(self.get_value_of(node, &predicate.right_side, bindings));
         let left_side_value = ok_or_return_false!(self.get_child_value(node, &predicate.left_side));
         right_side_value == left_side_value
     }
 
-    fn is_fit(&self, node: Node, mutation: &Mutation) -> bool {
+    fn fits_predicate_starts_with(
+        &self,
+        node: Node,
+        predicate: &PredicateStartsWith,
+        bindings: &Bindings,
+    ) -> bool {
+        let right_side_value =
+            ok_or_return_false!(self.get_value_of(node, &predicate.right_side, bindings));
+        let left_side_value = ok_or_return_false!(self.get_child_value(node, &predicate.left_side));
+        left_side_value.starts_with(&*right_side_value)
+    }
+
+    fn is_fit(&self, node: Node, mutation: &Mutation, bindings: &Bindings) -> bool {
         self.find_parent_elemnt(node, &mutation.get_clause.node_selector.path)
             .is_some()
             && self.fits_predicates(
@@ -65,6 +105,7 @@ impl Fitable for Xot {
                 } else {
                     vec![]
                 }),
+                bindings,
             )
             && self.fits_predicates(
                 node,
@@ -73,6 +114,34 @@ impl Fitable for Xot {
                 } else {
                     vec![]
                 }),
+                bindings,
+            )
+            && self.fits_predicates(
+                node,
+                &(if let Some(insert_clause) = &mutation.insert_clause {
+                    insert_clause.imply_predicates()
+                } else {
+                    vec![]
+                }),
+                bindings,
+            )
+            && self.fits_predicates(
+                node,
+                &(if let Some(move_clause) = &mutation.move_clause {
+                    move_clause.imply_predicates()
+                } else {
+                    vec![]
+                }),
+                bindings,
+            )
+            && self.fits_predicates(
+                node,
+                &(if let Some(wrap_clause) = &mutation.wrap_clause {
+                    wrap_clause.imply_predicates()
+                } else {
+                    vec![]
+                }),
+                bindings,
             )
     }
 }
@@ -3,6 +3,11 @@ mod fitable;
 mod macros;
 mod node_ext;
 mod operation;
+mod patchable;
+mod region;
+mod resolve;
+mod selectable;
+mod serialize;
 mod valuable;
 
 pub mod prelude {
@@ -10,5 +15,10 @@ pub mod prelude {
     pub use super::fitable::*;
     pub use super::node_ext::*;
     pub use super::operation::*;
+    pub use super::patchable::*;
+    pub use super::region::*;
+    pub use super::resolve::*;
+    pub use super::selectable::*;
+    pub use super::serialize::*;
     pub use super::valuable::*;
 }
@@ -1,14 +1,70 @@
-use crate::ok_or_return_false;
+use crate::{ok_or_return_false, operation::Indentation};
 use xot::{Node, Xot};
 
 pub trait NodeExtensions {
     fn tail_text_node(&self, node: Node) -> Option<Node>;
+    /// Text child written by `[text]`, the first one that is not blank or
+    /// else the first one
     fn text_node(&self, node: Node) -> Option<Node>;
     fn is_element_with_name(&self, node: Node, name: &str) -> bool;
     fn find_parent_elemnt(&self, node: Node, node_path: &[&str]) -> Option<Node>;
     fn find_first_child_element(&self, node: Node, node_path: &[&str]) -> Option<Node>;
+    /// All elements at the path in document order, an empty path is the node
+    fn find_child_elements(&self, node: Node, node_path: &[&str]) -> Vec<Node>;
     /// Returns the name without a namespace if it is an element
     fn get_name(&self, node: Node) -> Option<&str>;
+    /// Whether the closest `xml:space` of the node or its ancestors is `preserve`
+    fn preserves_space(&self, node: Node) -> bool;
+    /// Whitespace after the last newline before the node, if the node starts a line
+    fn line_indentation(&self, node: Node) -> Option<&str>;
+    /// Indentation for a new last child element of the node, `None` when
+    /// the node content is mixed or not laid out on separate lines
+    fn child_indentation(&self, node: Node) -> Option<Indentation>;
+    /// Whether the node is the only child of its parent apart from blank text
+    fn is_only_child(&self, node: Node) -> bool;
+    /// Children split after each element, so every group but the last ends
+    /// with an element and holds the comments and whitespace before it
+    fn element_groups(&self, node: Node) -> Vec<Vec<Node>>;
+    /// Children of the node in document order, for the document element
+    /// along with the comments and processing instructions around it
+    fn own_nodes(&self, node: Node) -> Vec<Node>;
+    /// First comment among the own nodes
+    fn comment_node(&self, node: Node) -> Option<Node>;
+    /// First processing instruction with the target among the own nodes
+    fn processing_instruction_node(&self, node: Node, target: &str) -> Option<Node>;
+}
+
+pub(crate) fn is_blank(text: &str) -> bool {
+    text.chars().all(char::is_whitespace)
+}
+
+/// Text written by every writer, lossless patches and serialization alike.
+/// `\r` is a character reference so parsers do not turn it into `\n`.
+pub fn escape_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#xD;")
+}
+
+/// Attribute value inside of `quote`. Tabs and line breaks are character
+/// references so parsers do not turn them into spaces.
+pub fn escape_attribute(value: &str, quote: char) -> String {
+    let value = value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('\t', "&#x9;")
+        .replace('\n', "&#xA;")
+        .replace('\r', "&#xD;");
+    match quote {
+        '\'' => value.replace('\'', "&apos;"),
+        _ => value.replace('"', "&quot;"),
+    }
+}
+
+fn after_last_newline(text: &str) -> Option<&str> {
+    text.rfind('\n').map(|i| &text[i + 1..])
 }
 
 impl NodeExtensions for Xot {
@@ -21,12 +77,12 @@ impl NodeExtensions for Xot {
         }
     }
     fn text_node(&self, node: Node) -> Option<Node> {
-        let node = self.first_child(node)?;
-        if self.is_text(node) {
-            Some(node)
-        } else {
-            None
-        }
+        let mut texts = self.children(node).filter(|n| self.is_text(*n));
+        let first = texts.next()?;
+        std::iter::once(first)
+            .chain(texts)
+            .find(|n| self.text_str(*n).is_some_and(|t| !is_blank(t)))
+            .or(Some(first))
     }
     fn is_element_with_name(&self, node: Node, name: &str) -> bool {
         let element = ok_or_return_false!(self.element(node)); // element access panics! unreachable!("Try to access a freed node")
@@ -62,8 +118,116 @@ impl NodeExtensions for Xot {
         }
         Some(current_node)
     }
+    fn find_child_elements(&self, node: Node, node_path: &[&str]) -> Vec<Node> {
+        let mut nodes = vec![node];
+        for &name in node_path {
+            nodes = nodes
+                .into_iter()
+                .flat_map(|n| self.children(n))
+                .filter(|n| self.is_element_with_name(*n, name))
+                .collect();
+        }
+        nodes
+    }
     fn get_name(&self, node: Node) -> Option<&str> {
         let (name, _) = self.name_ns_str(self.element(node)?.name());
         Some(name)
     }
+    fn preserves_space(&self, node: Node) -> bool {
+        let xml_space = self.xml_space_name();
+        std::iter::once(node)
+            .chain(self.ancestors(node))
+            .find_map(|n| self.get_attribute(n, xml_space))
+            .is_some_and(|space| space == "preserve")
+    }
+    fn line_indentation(&self, node: Node) -> Option<&str> {
+        if self.is_document_element(node) {
+            return Some("");
+        }
+        let text = self.text_str(self.previous_sibling(node)?)?;
+        let indentation = after_last_newline(text)?;
+        is_blank(indentation).then_some(indentation)
+    }
+    fn child_indentation(&self, node: Node) -> Option<Indentation> {
+        if self.preserves_space(node)
+            || self
+                .children(node)
+                .any(|n| self.text_str(n).is_some_and(|t| !is_blank(t)))
+        {
+            return None;
+        }
+        let children_indentation = self
+            .children(node)
+            .filter(|n| self.is_element(*n))
+            .filter_map(|n| self.line_indentation(n))
+            .last();
+        let closing = match self.last_child(node).and_then(|n| self.text_str(n)) {
+            Some(text) => after_last_newline(text)?,
+            None => self.line_indentation(node)?,
+        };
+        // one level is the difference between a node and its parent
+        // indentation, first found from the node upwards
+        let unit = std::iter::once(node)
+            .chain(self.ancestors(node))
+            .filter(|n| self.is_element(*n))
+            .find_map(|n| {
+                let parent_indentation = self.line_indentation(self.parent(n)?)?;
+                self.line_indentation(n)?
+                    .strip_prefix(parent_indentation)
+                    .filter(|u| !u.is_empty())
+            })
+            .or(children_indentation.and_then(|c| c.strip_prefix(closing)))
+            .filter(|u| !u.is_empty())
+            .unwrap_or("  ");
+        let child = match children_indentation {
+            Some(child) => child.to_string(),
+            None => format!("{}{}", closing, unit),
+        };
+
+        Some(Indentation {
+            child,
+            closing: closing.to_string(),
+            unit: unit.to_string(),
+        })
+    }
+    fn element_groups(&self, node: Node) -> Vec<Vec<Node>> {
+        let mut groups = vec![];
+        let mut group = vec![];
+        for child in self.children(node) {
+            group.push(child);
+            if self.is_element(child) {
+                groups.push(std::mem::take(&mut group));
+            }
+        }
+        groups.push(group);
+        groups
+    }
+    fn is_only_child(&self, node: Node) -> bool {
+        let parent = ok_or_return_false!(self.parent(node));
+        self.children(parent)
+            .all(|n| n == node || self.text_str(n).is_some_and(is_blank))
+    }
+    fn own_nodes(&self, node: Node) -> Vec<Node> {
+        match self.parent(node) {
+            Some(root) if self.is_document_element(node) => self
+                .children(root)
+                .flat_map(|n| match n == node {
+                    true => self.children(node).collect(),
+                    false => vec![n],
+                })
+                .collect(),
+            _ => self.children(node).collect(),
+        }
+    }
+    fn comment_node(&self, node: Node) -> Option<Node> {
+        self.own_nodes(node)
+            .into_iter()
+            .find(|n| self.is_comment(*n))
+    }
+    fn processing_instruction_node(&self, node: Node, target: &str) -> Option<Node> {
+        self.own_nodes(node).into_iter().find(|n| {
+            self.processing_instruction(*n)
+                .is_some_and(|pi| self.local_name_str(pi.target()) == target)
+        })
+    }
 }
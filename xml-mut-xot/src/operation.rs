@@ -4,10 +4,23 @@ pub enum Operation {
     AddSubTree(OpAddSubTree),
     SetAttribute(OpSetAttribute),
     RemoveAttribute(OpRemoveAttribute),
+    RenameAttribute(OpRenameAttribute),
     SetText(OpSetText),
     PrependText(OpPrependText),
     SetTextAfter(OpSetTextAfter),
     SetName(OpSetName),
+    SetNodeValue(OpSetNodeValue),
+    SetInner(OpSetInner),
+    ReplaceNode(OpReplaceNode),
+    InsertElement(OpInsertElement),
+    InsertNode(OpInsertNode),
+    MoveNodes(OpMoveNodes),
+    CopyNodes(OpCopyNodes),
+    WrapNode(OpWrapNode),
+    UnwrapNode(OpUnwrapNode),
+    SortChildren(OpSortChildren),
+    /// Deletes an element that has the same key as a kept sibling
+    DeleteDuplicate(OpDeleteNode),
     DeleteNode(OpDeleteNode),
 }
 
@@ -15,6 +28,103 @@ pub struct OpAddSubTree {
     pub node: Node,
     pub node_path: Vec<String>,
     pub sub_op: SubOperation,
+    /// Whitespace around the new elements, `None` keeps them inline
+    pub indentation: Option<Indentation>,
+}
+
+/// Indentation copied from the siblings (or the parent) of a new element.
+/// Each new line starts with a newline followed by one of these.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Indentation {
+    /// Indentation before the new element
+    pub child: String,
+    /// Indentation before the end tag of the parent
+    pub closing: String,
+    /// One level of indentation, used for nested new elements
+    pub unit: String,
+}
+
+impl Indentation {
+    /// Indentation of elements created inside the new element
+    pub fn nested(&self) -> Indentation {
+        Indentation {
+            child: format!("{}{}", self.child, self.unit),
+            closing: self.child.clone(),
+            unit: self.unit.clone(),
+        }
+    }
+}
+
+impl Operation {
+    /// Name of the operation variant, e.g. `SetAttribute`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Operation::AddSubTree(_) => "AddSubTree",
+            Operation::SetAttribute(_) => "SetAttribute",
+            Operation::RemoveAttribute(_) => "RemoveAttribute",
+            Operation::RenameAttribute(_) => "RenameAttribute",
+            Operation::SetText(_) => "SetText",
+            Operation::PrependText(_) => "PrependText",
+            Operation::SetTextAfter(_) => "SetTextAfter",
+            Operation::SetName(_) => "SetName",
+            Operation::SetNodeValue(_) => "SetNodeValue",
+            Operation::SetInner(_) => "SetInner",
+            Operation::ReplaceNode(_) => "ReplaceNode",
+            Operation::InsertElement(_) => "InsertElement",
+            Operation::InsertNode(_) => "InsertNode",
+            Operation::MoveNodes(_) => "MoveNodes",
+            Operation::CopyNodes(_) => "CopyNodes",
+            Operation::WrapNode(_) => "WrapNode",
+            Operation::UnwrapNode(_) => "UnwrapNode",
+            Operation::SortChildren(_) => "SortChildren",
+            Operation::DeleteDuplicate(_) => "DeleteDuplicate",
+            Operation::DeleteNode(_) => "DeleteNode",
+        }
+    }
+
+    /// Nodes the operation takes out of their parent
+    pub fn removed_nodes(&self) -> Vec<Node> {
+        match self {
+            Operation::DeleteNode(op) | Operation::DeleteDuplicate(op) => vec![op.node],
+            Operation::MoveNodes(op) => op.nodes.clone(),
+            _ => vec![],
+        }
+    }
+
+    /// Same operation with new elements kept inline
+    pub fn unindented(self) -> Operation {
+        match self {
+            Operation::AddSubTree(op) => Operation::AddSubTree(OpAddSubTree {
+                indentation: None,
+                ..op
+            }),
+            Operation::InsertElement(op) => Operation::InsertElement(OpInsertElement {
+                indentation: None,
+                ..op
+            }),
+            Operation::InsertNode(op) => Operation::InsertNode(OpInsertNode {
+                indentation: None,
+                ..op
+            }),
+            Operation::MoveNodes(op) => Operation::MoveNodes(OpMoveNodes {
+                indentation: None,
+                ..op
+            }),
+            Operation::CopyNodes(op) => Operation::CopyNodes(OpCopyNodes {
+                indentation: None,
+                ..op
+            }),
+            Operation::WrapNode(op) => Operation::WrapNode(OpWrapNode {
+                indentation: None,
+                ..op
+            }),
+            Operation::UnwrapNode(op) => Operation::UnwrapNode(OpUnwrapNode {
+                indentation: None,
+                ..op
+            }),
+            op => op,
+        }
+    }
 }
 
 pub enum SubOperation {
@@ -25,6 +135,8 @@ pub enum SubOperation {
     AddText(String),
     /// Value of the tail text to be inserted after
     AddTailText(String),
+    /// Xml markup to be added as children
+    AddXml(String),
 }
 
 pub struct OpSetAttribute {
@@ -36,6 +148,12 @@ pub struct OpRemoveAttribute {
     pub node: Node,
     pub name: String,
 }
+/// Renames the attribute in place, replacing any attribute with the new name
+pub struct OpRenameAttribute {
+    pub node: Node,
+    pub name: String,
+    pub new_name: String,
+}
 pub struct OpSetText {
     pub node: Node,
     pub value: String,
@@ -56,6 +174,96 @@ pub struct OpSetName {
     pub name: String,
 }
 
+/// Sets the content of a comment or the data of a processing instruction
+pub struct OpSetNodeValue {
+    pub node: Node,
+    pub value: String,
+}
+
+/// Replaces all children of the node with xml markup
+pub struct OpSetInner {
+    pub node: Node,
+    pub xml: String,
+}
+
+/// Replaces the node itself with xml markup
+pub struct OpReplaceNode {
+    pub node: Node,
+    pub xml: String,
+}
+
+/// Where a new node goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// First child of the node
+    FirstIn(Node),
+    /// Last child of the node
+    LastIn(Node),
+    /// Sibling right before the node
+    Before(Node),
+    /// Sibling right after the node
+    After(Node),
+}
+
+pub struct OpInsertElement {
+    pub position: Position,
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: Option<String>,
+    /// Indentation of the new element line, `None` keeps it inline
+    pub indentation: Option<Indentation>,
+}
+
+/// Places a comment or a processing instruction given as xml markup
+pub struct OpInsertNode {
+    pub position: Position,
+    pub xml: String,
+    /// Indentation of the new node line, `None` keeps it inline
+    pub indentation: Option<Indentation>,
+}
+
+/// Moves the nodes, in order, to the position
+pub struct OpMoveNodes {
+    pub nodes: Vec<Node>,
+    pub position: Position,
+    /// Indentation of the moved element lines, `None` keeps them inline
+    pub indentation: Option<Indentation>,
+}
+
+/// Places copies of the nodes, in order, at the position
+pub struct OpCopyNodes {
+    pub nodes: Vec<Node>,
+    pub position: Position,
+    /// Indentation of the copied element lines, `None` keeps them inline
+    pub indentation: Option<Indentation>,
+}
+
+/// Puts sibling nodes, with the whitespace between them, into new elements
+pub struct OpWrapNode {
+    /// Siblings in document order, only whitespace is between them
+    pub nodes: Vec<Node>,
+    /// Names and attributes of the new elements, outermost first
+    pub wrappers: Vec<(String, Vec<(String, String)>)>,
+    /// `child` is the indentation of the node line, each wrapper nests the
+    /// lines inside of it one `unit` deeper. `None` keeps them inline
+    pub indentation: Option<Indentation>,
+}
+
+/// Replaces the node with its children
+pub struct OpUnwrapNode {
+    pub node: Node,
+    /// Indentation of the node children, lines inside of the node move from
+    /// `child` to `closing`. `None` keeps the children as they are
+    pub indentation: Option<Indentation>,
+}
+
+/// Puts the child elements in the order given, each with the comments and
+/// whitespace before it. Other children keep their places.
+pub struct OpSortChildren {
+    pub node: Node,
+    pub elements: Vec<Node>,
+}
+
 pub struct OpDeleteNode {
     pub node: Node,
 }
@@ -0,0 +1,774 @@
+use crate::{
+    node_ext::{escape_attribute, escape_text},
+    operation::*,
+    prelude::{Error, NodeExtensions},
+};
+use std::ops::Range;
+use xot::{Node, SpanInfo, SpanInfoKey, Xot};
+
+/// A single source text edit. An empty `range` is an insertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub range: Range<usize>,
+    pub text: String,
+    /// Closing tags of expanded empty elements go after any other
+    /// insertion at the same position.
+    pub closing: bool,
+    /// Source range written instead of `text`, along with the patches
+    /// inside of it. Patches inside of `range` go with the copy of it.
+    pub from: Option<Range<usize>>,
+}
+
+impl Patch {
+    pub fn replace(range: Range<usize>, text: String) -> Self {
+        Patch {
+            range,
+            text,
+            closing: false,
+            from: None,
+        }
+    }
+    pub fn copy(range: Range<usize>, from: Range<usize>) -> Self {
+        Patch {
+            from: Some(from),
+            ..Self::replace(range, String::new())
+        }
+    }
+    pub fn insert(at: usize, text: String) -> Self {
+        Self::replace(at..at, text)
+    }
+    pub fn remove(range: Range<usize>) -> Self {
+        Self::replace(range, String::new())
+    }
+}
+
+/// Lossless editing: instead of applying operations to the tree and
+/// serializing it again, operations are turned into patches of the
+/// original source text. Bytes outside of patched ranges are kept as is.
+/// Operations must be gathered from a tree parsed with
+/// [`Xot::parse_with_span_info`] before it was mutated.
+pub trait Patchable {
+    fn get_patches(
+        &self,
+        source: &str,
+        span_info: &SpanInfo,
+        operation: &Operation,
+    ) -> Result<Vec<Patch>, Error>;
+    fn patch_all(
+        &self,
+        source: &str,
+        span_info: &SpanInfo,
+        operations: &[Operation],
+    ) -> Result<String, Error>;
+}
+
+fn span(span_info: &SpanInfo, key: SpanInfoKey) -> Result<Range<usize>, Error> {
+    span_info
+        .get(key)
+        .map(|s| s.range())
+        .ok_or(Error::SpanNotFound)
+}
+
+/// Text as a CDATA section, `]]>` inside of it is split over two sections.
+/// Whitespace around the text is left outside of the section.
+pub fn cdata_section(value: &str) -> String {
+    let content = value.trim();
+    if content.is_empty() {
+        return value.to_string();
+    }
+    let start = value.len() - value.trim_start().len();
+    format!(
+        "{}<![CDATA[{}]]>{}",
+        &value[..start],
+        content.replace("]]>", "]]]]><![CDATA[>"),
+        &value[start + content.len()..]
+    )
+}
+
+/// Position of the `>` closing the start tag of an element.
+fn start_tag_close(source: &str, name_end: usize) -> Result<usize, Error> {
+    let mut quote = None;
+    for (i, c) in source[name_end..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Ok(name_end + i),
+            _ => (),
+        }
+    }
+    Err(Error::SpanNotFound)
+}
+
+/// Position right after the last attribute (or the name) of a start tag,
+/// before any whitespace and before `/>` or `>`.
+fn start_tag_content_end(source: &str, name_end: usize) -> Result<usize, Error> {
+    let close = start_tag_close(source, name_end)?;
+    let trimmed = source[..close].trim_end_matches('/').trim_end();
+    Ok(trimmed.len().max(name_end))
+}
+
+/// Replaces everything after the attributes of an element with `/>`.
+fn empty_element(source: &str, span_info: &SpanInfo, element: Node) -> Result<Patch, Error> {
+    let name = span(span_info, SpanInfoKey::ElementStart(element))?;
+    let end = span(span_info, SpanInfoKey::ElementEnd(element))?;
+    let at = start_tag_content_end(source, name.end)?;
+    Ok(Patch::replace(at..end.end, "/>".to_string()))
+}
+
+/// Source range of an element, a comment or a processing instruction
+fn node_range(
+    xot: &Xot,
+    source: &str,
+    span_info: &SpanInfo,
+    node: Node,
+) -> Result<Range<usize>, Error> {
+    if xot.is_comment(node) {
+        let content = span(span_info, SpanInfoKey::Comment(node))?;
+        Ok(content.start - "<!--".len()..content.end + "-->".len())
+    } else if xot.is_processing_instruction(node) {
+        let target = span(span_info, SpanInfoKey::PiTarget(node))?;
+        let end = source[target.end..].find("?>").ok_or(Error::SpanNotFound)?;
+        Ok(target.start - "<?".len()..target.end + end + "?>".len())
+    } else {
+        // element name span starts right after `<`
+        let name = span(span_info, SpanInfoKey::ElementStart(node))?;
+        Ok(name.start - 1..span(span_info, SpanInfoKey::ElementEnd(node))?.end)
+    }
+}
+
+/// Removes the node, `empties_parent` when nothing but whitespace would be
+/// left in its parent
+fn delete_patches(
+    xot: &Xot,
+    source: &str,
+    span_info: &SpanInfo,
+    node: Node,
+    empties_parent: bool,
+) -> Result<Vec<Patch>, Error> {
+    let range = node_range(xot, source, span_info, node)?;
+    Ok(if xot.preserves_space(node) {
+        vec![Patch::remove(range)]
+    } else if empties_parent {
+        // turn the parent into an empty element
+        let parent = xot.parent(node).ok_or(Error::NotAnElement)?;
+        vec![empty_element(source, span_info, parent)?]
+    } else {
+        vec![Patch::remove(line_range(source, range))]
+    })
+}
+
+/// Element the operation adds content to
+fn filled_element(xot: &Xot, operation: &Operation) -> Option<Node> {
+    let position = match operation {
+        Operation::InsertElement(op) => op.position,
+        Operation::InsertNode(op) => op.position,
+        Operation::MoveNodes(op) => op.position,
+        Operation::CopyNodes(op) => op.position,
+        Operation::AddSubTree(op) => return Some(op.node),
+        Operation::SetText(op) => return Some(op.node),
+        Operation::PrependText(op) => return Some(op.node),
+        Operation::SetInner(op) => return Some(op.node),
+        _ => return None,
+    };
+    match position {
+        Position::FirstIn(node) | Position::LastIn(node) => Some(node),
+        Position::Before(node) | Position::After(node) => xot.parent(node),
+    }
+}
+
+/// Extends a text span to cover CDATA delimiters it starts or ends inside.
+pub(crate) fn text_range(source: &str, range: Range<usize>) -> Range<usize> {
+    let start = if source[..range.start].ends_with("<![CDATA[") {
+        range.start - "<![CDATA[".len()
+    } else {
+        range.start
+    };
+    let end = if source[range.end..].starts_with("]]>") {
+        range.end + "]]>".len()
+    } else {
+        range.end
+    };
+    start..end
+}
+
+/// Extends a removed element range over its own line when nothing else is
+/// on that line, so no blank line is left behind. The line break before the
+/// element goes with it rather than the one after it, the range then stays
+/// within the source that `SORT` moves along with the element.
+fn line_range(source: &str, range: Range<usize>) -> Range<usize> {
+    let before = &source[..range.start];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    if !before[line_start..].trim().is_empty() {
+        return range;
+    }
+    let after = &source[range.end..];
+    let line_end = after.find('\n').map(|i| range.end + i + 1);
+    match line_end {
+        Some(line_end) if source[range.end..line_end].trim().is_empty() => {
+            if line_start == 0 {
+                return line_start..line_end;
+            }
+            let break_start = source[..line_start - 1]
+                .strip_suffix('\r')
+                .map_or(line_start - 1, str::len);
+            // trailing whitespace of the line is still removed
+            let trailing = source[range.end..line_end]
+                .trim_end_matches(['\r', '\n'])
+                .len();
+            break_start..range.end + trailing
+        }
+        _ => range,
+    }
+}
+
+/// Xml of new nested elements, `indentation` is the one of their children.
+fn sub_tree_xml(
+    node_path: &[String],
+    sub_op: &SubOperation,
+    indentation: Option<&Indentation>,
+) -> String {
+    let (name, rest) = match node_path.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+    if !rest.is_empty() {
+        let inner = sub_tree_xml(rest, sub_op, indentation.map(|i| i.nested()).as_ref());
+        return match indentation {
+            Some(ind) => format!(
+                "<{}>\n{}{}\n{}</{}>",
+                name, ind.child, inner, ind.closing, name
+            ),
+            None => format!("<{}>{}</{}>", name, inner, name),
+        };
+    }
+    match sub_op {
+        SubOperation::None => format!("<{}/>", name),
+        SubOperation::AddAttribute(attr, value) => {
+            format!("<{} {}=\"{}\"/>", name, attr, escape_attribute(value, '"'))
+        }
+        SubOperation::AddText(value) => format!("<{}>{}</{}>", name, escape_text(value), name),
+        SubOperation::AddTailText(value) => format!("<{}/>{}", name, escape_text(value)),
+        SubOperation::AddXml(xml) => format!("<{}>{}</{}>", name, xml, name),
+    }
+}
+
+/// Name and attributes of a new element, without the `<` and `>`
+fn start_tag(name: &str, attributes: &[(String, String)]) -> String {
+    let attributes: String = attributes
+        .iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, escape_attribute(value, '"')))
+        .collect();
+    format!("{}{}", name, attributes)
+}
+
+/// Xml of an inserted element
+fn element_xml(name: &str, attributes: &[(String, String)], text: Option<&str>) -> String {
+    let tag = start_tag(name, attributes);
+    match text {
+        Some(text) => format!("<{}>{}</{}>", tag, escape_text(text), name),
+        None => format!("<{}/>", tag),
+    }
+}
+
+/// The source range with the sorted patches inside of it applied. An
+/// insertion at the very start of a copied range belongs to what is before
+/// it, one at the end goes with the copy.
+fn render(source: &str, range: Range<usize>, patches: &[Patch]) -> Result<String, Error> {
+    let inside = |p: &&Patch| {
+        range.start <= p.range.start
+            && p.range.end <= range.end
+            && (range.start == 0 || !p.range.is_empty() || p.range.start > range.start)
+    };
+    let mut xml = String::with_capacity(range.len());
+    let mut cursor = range.start;
+    let mut copied_end = None;
+    for patch in patches.iter().filter(inside) {
+        let copied = patch.range.is_empty() && copied_end == Some(patch.range.start);
+        if patch.range.start < cursor || copied {
+            // new content inside of a removed or replaced range would be
+            // lost, a change to what is removed is not
+            if patch.range.end <= cursor && (copied || !patch.range.is_empty()) {
+                // already covered by a removed, replaced or copied range
+                continue;
+            }
+            return Err(Error::OverlappingPatches);
+        }
+        xml.push_str(&source[cursor..patch.range.start]);
+        match &patch.from {
+            Some(from) => {
+                // the copy is rendered without the patch that replaces it
+                let others: Vec<Patch> = patches
+                    .iter()
+                    .filter(|p| !(p.from.is_some() && p.range == *from))
+                    .cloned()
+                    .collect();
+                xml.push_str(&render(source, from.clone(), &others)?);
+                copied_end = Some(patch.range.end);
+            }
+            None => xml.push_str(&patch.text),
+        }
+        cursor = patch.range.end;
+    }
+    xml.push_str(&source[cursor..range.end]);
+
+    Ok(xml)
+}
+
+impl Patchable for Xot {
+    fn get_patches(
+        &self,
+        source: &str,
+        span_info: &SpanInfo,
+        operation: &Operation,
+    ) -> Result<Vec<Patch>, Error> {
+        let element_name = |node: Node| span(span_info, SpanInfoKey::ElementStart(node));
+        let element_end = |node: Node| span(span_info, SpanInfoKey::ElementEnd(node));
+        let is_empty_element =
+            |node: Node| -> Result<bool, Error> { Ok(&source[element_end(node)?] == "/>") };
+        let add_attribute = |node: Node, name: &str, value: &str| -> Result<Patch, Error> {
+            let at = start_tag_content_end(source, element_name(node)?.end)?;
+            Ok(Patch::insert(
+                at,
+                format!(" {}=\"{}\"", name, escape_attribute(value, '"')),
+            ))
+        };
+        // content is appended (or prepended) to the element, empty elements
+        // like `<a/>` are expanded to `<a>content</a>`
+        let add_content =
+            |node: Node, content: String, prepend: bool| -> Result<Vec<Patch>, Error> {
+                let name = element_name(node)?;
+                if is_empty_element(node)? {
+                    let at = start_tag_content_end(source, name.end)?;
+                    let end = element_end(node)?.end;
+                    Ok(vec![
+                        Patch::replace(at..end, ">".to_string()),
+                        Patch::insert(end, content),
+                        Patch {
+                            range: end..end,
+                            text: format!("</{}>", &source[name]),
+                            closing: true,
+                            from: None,
+                        },
+                    ])
+                } else if prepend {
+                    let at = start_tag_close(source, name.end)? + 1;
+                    Ok(vec![Patch::insert(at, content)])
+                } else {
+                    Ok(vec![Patch::insert(element_end(node)?.start, content)])
+                }
+            };
+
+        // a new last child, on its own line when there is indentation
+        let add_last = |node: Node,
+                        xml: String,
+                        indentation: Option<&Indentation>|
+         -> Result<Vec<Patch>, Error> {
+            let trailing_whitespace = self
+                .last_child(node)
+                .filter(|n| self.text_str(*n).is_some_and(|t| t.trim().is_empty()));
+            match (indentation, trailing_whitespace) {
+                (None, _) => add_content(node, xml, false),
+                // new lines go right before the indentation of the end tag,
+                // anything anchored to the end of the last child stays first
+                (Some(ind), Some(text)) => {
+                    let range = span(span_info, SpanInfoKey::Text(text))?;
+                    Ok(vec![match source[range.clone()].rfind('\n') {
+                        Some(i) => {
+                            Patch::insert(range.start + i + 1, format!("{}{}\n", ind.child, xml))
+                        }
+                        None => Patch::insert(range.start, format!("\n{}{}", ind.child, xml)),
+                    }])
+                }
+                (Some(ind), None) => add_content(
+                    node,
+                    format!("\n{}{}\n{}", ind.child, xml, ind.closing),
+                    false,
+                ),
+            }
+        };
+
+        // new xml at the position, on its own line when there is indentation
+        let place = |position: Position,
+                     xml: String,
+                     indentation: Option<&Indentation>|
+         -> Result<Vec<Patch>, Error> {
+            let line = |xml: String| match indentation {
+                Some(ind) => format!("\n{}{}", ind.child, xml),
+                None => xml,
+            };
+            Ok(match position {
+                Position::FirstIn(parent) if self.first_child(parent).is_some() => {
+                    let at = start_tag_close(source, element_name(parent)?.end)? + 1;
+                    vec![Patch::insert(at, line(xml))]
+                }
+                // nothing to go before, same as appending
+                Position::FirstIn(parent) | Position::LastIn(parent) => {
+                    add_last(parent, xml, indentation)?
+                }
+                Position::Before(sibling) => {
+                    let at = element_name(sibling)?.start - 1;
+                    let text = match indentation {
+                        Some(ind) => format!("{}\n{}", xml, ind.child),
+                        None => xml,
+                    };
+                    vec![Patch::insert(at, text)]
+                }
+                Position::After(sibling) => {
+                    vec![Patch::insert(element_end(sibling)?.end, line(xml))]
+                }
+            })
+        };
+        // source xml of the elements, one per line when there is
+        // indentation, inner lines shifted to the new indentation
+        let nodes_xml = |nodes: &[Node], indentation: Option<&Indentation>| {
+            let separator = match indentation {
+                Some(ind) => format!("\n{}", ind.child),
+                None => String::new(),
+            };
+            nodes
+                .iter()
+                .map(|&node| {
+                    let start = element_name(node)?.start - 1;
+                    let xml = &source[start..element_end(node)?.end];
+                    Ok(match (indentation, self.line_indentation(node)) {
+                        (Some(_), Some(from)) if !self.preserves_space(node) => {
+                            xml.replace(&format!("\n{}", from), &separator)
+                        }
+                        _ => xml.to_string(),
+                    })
+                })
+                .collect::<Result<Vec<String>, Error>>()
+                .map(|xmls| xmls.join(&separator))
+        };
+
+        let patches = match operation {
+            Operation::AddSubTree(op) => {
+                if op.node_path.is_empty() {
+                    match &op.sub_op {
+                        SubOperation::None => vec![],
+                        SubOperation::AddAttribute(name, value) => self.get_patches(
+                            source,
+                            span_info,
+                            &Operation::SetAttribute(OpSetAttribute {
+                                node: op.node,
+                                name: name.clone(),
+                                value: value.clone(),
+                            }),
+                        )?,
+                        SubOperation::AddText(value) => {
+                            add_content(op.node, escape_text(value), false)?
+                        }
+                        SubOperation::AddTailText(value) => {
+                            vec![Patch::insert(element_end(op.node)?.end, escape_text(value))]
+                        }
+                        SubOperation::AddXml(xml) if xml.is_empty() => vec![],
+                        SubOperation::AddXml(xml) => add_content(op.node, xml.clone(), false)?,
+                    }
+                } else {
+                    let nested = op.indentation.as_ref().map(|i| i.nested());
+                    let xml = sub_tree_xml(&op.node_path, &op.sub_op, nested.as_ref());
+                    add_last(op.node, xml, op.indentation.as_ref())?
+                }
+            }
+            Operation::SetAttribute(op) => {
+                let existing = self
+                    .name(op.name.as_str())
+                    .and_then(|name_id| {
+                        span_info.get(SpanInfoKey::AttributeValue(op.node, name_id))
+                    })
+                    .map(|s| s.range());
+                match existing {
+                    Some(range) => {
+                        let quote = source[..range.start].chars().last().unwrap_or('"');
+                        vec![Patch::replace(range, escape_attribute(&op.value, quote))]
+                    }
+                    None => vec![add_attribute(op.node, &op.name, &op.value)?],
+                }
+            }
+            Operation::RemoveAttribute(op) => {
+                let name_id = self
+                    .name(op.name.as_str())
+                    .ok_or(Error::NameNotFound(op.name.clone()))?;
+                match span_info.get(SpanInfoKey::AttributeName(op.node, name_id)) {
+                    Some(name_span) => {
+                        let value = span(span_info, SpanInfoKey::AttributeValue(op.node, name_id))?;
+                        let start = source[..name_span.start].trim_end().len();
+                        // value span excludes the closing quote
+                        vec![Patch::remove(start..value.end + 1)]
+                    }
+                    None => vec![],
+                }
+            }
+            Operation::RenameAttribute(op) => {
+                let name_id = self
+                    .name(op.name.as_str())
+                    .ok_or(Error::NameNotFound(op.name.clone()))?;
+                let mut patches = vec![Patch::replace(
+                    span(span_info, SpanInfoKey::AttributeName(op.node, name_id))?,
+                    op.new_name.clone(),
+                )];
+                // an attribute with the new name is replaced
+                if op.new_name != op.name {
+                    let existing = Operation::RemoveAttribute(OpRemoveAttribute {
+                        node: op.node,
+                        name: op.new_name.clone(),
+                    });
+                    if self.name(op.new_name.as_str()).is_some() {
+                        patches.append(&mut self.get_patches(source, span_info, &existing)?);
+                    }
+                }
+                patches
+            }
+            Operation::SetText(op) => {
+                let range = text_range(source, span(span_info, SpanInfoKey::Text(op.node))?);
+                // text written in CDATA stays in CDATA
+                let text = if source[range.clone()].contains("<![CDATA[") && !op.value.is_empty() {
+                    cdata_section(&op.value)
+                } else {
+                    escape_text(&op.value)
+                };
+                vec![Patch::replace(range, text)]
+            }
+            Operation::PrependText(op) => add_content(op.node, escape_text(&op.value), true)?,
+            Operation::SetTextAfter(op) => {
+                vec![Patch::insert(
+                    element_end(op.node)?.end,
+                    escape_text(&op.value),
+                )]
+            }
+            Operation::SetName(op) => {
+                let mut patches = vec![Patch::replace(element_name(op.node)?, op.name.clone())];
+                if !is_empty_element(op.node)? {
+                    patches.push(Patch::replace(
+                        element_end(op.node)?,
+                        format!("</{}>", op.name),
+                    ));
+                }
+                patches
+            }
+            Operation::SetNodeValue(op) if self.is_comment(op.node) => {
+                vec![Patch::replace(
+                    span(span_info, SpanInfoKey::Comment(op.node))?,
+                    format!(" {} ", op.value),
+                )]
+            }
+            Operation::SetNodeValue(op) => match span_info.get(SpanInfoKey::PiContent(op.node)) {
+                Some(content) => vec![Patch::replace(content.range(), op.value.clone())],
+                None => vec![Patch::insert(
+                    span(span_info, SpanInfoKey::PiTarget(op.node))?.end,
+                    format!(" {}", op.value),
+                )],
+            },
+            Operation::SetInner(op) => {
+                if !is_empty_element(op.node)? {
+                    let start = start_tag_close(source, element_name(op.node)?.end)? + 1;
+                    vec![Patch::replace(
+                        start..element_end(op.node)?.start,
+                        op.xml.clone(),
+                    )]
+                } else if op.xml.is_empty() {
+                    vec![]
+                } else {
+                    add_content(op.node, op.xml.clone(), false)?
+                }
+            }
+            Operation::ReplaceNode(op) => {
+                let start = element_name(op.node)?.start - 1;
+                vec![Patch::replace(
+                    start..element_end(op.node)?.end,
+                    op.xml.clone(),
+                )]
+            }
+            Operation::InsertElement(op) => {
+                let xml = element_xml(&op.name, &op.attributes, op.text.as_deref());
+                place(op.position, xml, op.indentation.as_ref())?
+            }
+            Operation::InsertNode(op) => {
+                place(op.position, op.xml.clone(), op.indentation.as_ref())?
+            }
+            Operation::MoveNodes(op) => {
+                let mut patches = place(
+                    op.position,
+                    nodes_xml(&op.nodes, op.indentation.as_ref())?,
+                    op.indentation.as_ref(),
+                )?;
+                for &node in &op.nodes {
+                    let delete = Operation::DeleteNode(OpDeleteNode { node });
+                    patches.append(&mut self.get_patches(source, span_info, &delete)?);
+                }
+                patches
+            }
+            Operation::CopyNodes(op) => place(
+                op.position,
+                nodes_xml(&op.nodes, op.indentation.as_ref())?,
+                op.indentation.as_ref(),
+            )?,
+            Operation::WrapNode(op) => {
+                let line = |level: usize| match &op.indentation {
+                    Some(ind) => format!("\n{}{}", ind.child, ind.unit.repeat(level)),
+                    None => String::new(),
+                };
+                let first = *op.nodes.first().ok_or(Error::NothingToAdd)?;
+                let last = *op.nodes.last().ok_or(Error::NothingToAdd)?;
+                let start = element_name(first)?.start - 1;
+                let end = element_end(last)?.end;
+                let mut opening = String::new();
+                let mut closing = String::new();
+                for (level, (name, attributes)) in op.wrappers.iter().enumerate() {
+                    opening.push_str(&format!(
+                        "<{}>{}",
+                        start_tag(name, attributes),
+                        line(level + 1)
+                    ));
+                    closing.insert_str(0, &format!("{}</{}>", line(level), name));
+                }
+                let mut patches = vec![Patch::insert(start, opening), Patch::insert(end, closing)];
+                // lines inside of the nodes and between them go deeper
+                if let Some(ind) = &op.indentation {
+                    let deeper = ind.unit.repeat(op.wrappers.len());
+                    let from = format!("\n{}", ind.child);
+                    for (i, _) in source[start..end].match_indices(&from) {
+                        patches.push(Patch::insert(start + i + from.len(), deeper.clone()));
+                    }
+                }
+                patches
+            }
+            Operation::UnwrapNode(op) => {
+                let name = element_name(op.node)?;
+                let end_tag = element_end(op.node)?;
+                let (start, end) = match &op.indentation {
+                    // the line breaks around the children go with the tags
+                    Some(_) => {
+                        let first = self.first_child(op.node).ok_or(Error::NotATextNode)?;
+                        let last = self.last_child(op.node).ok_or(Error::NotATextNode)?;
+                        let first = span(span_info, SpanInfoKey::Text(first))?;
+                        let last = span(span_info, SpanInfoKey::Text(last))?;
+                        let leading = source[first.clone()].trim_start().len();
+                        let trailing = source[last.clone()].trim_end().len();
+                        (first.end - leading, last.start + trailing)
+                    }
+                    None => (start_tag_close(source, name.end)? + 1, end_tag.start),
+                };
+                let mut patches = vec![
+                    Patch::remove(name.start - 1..start),
+                    Patch::remove(end..end_tag.end),
+                ];
+                if let Some(ind) = &op.indentation {
+                    let from = format!("\n{}", ind.child);
+                    for (i, _) in source[start..end].match_indices(&from) {
+                        let at = start + i + 1;
+                        patches.push(Patch::replace(
+                            at..at + ind.child.len(),
+                            ind.closing.clone(),
+                        ));
+                    }
+                }
+                patches
+            }
+            Operation::SortChildren(op) => {
+                // each element goes with the source between it and the
+                // element before it
+                let mut start = start_tag_close(source, element_name(op.node)?.end)? + 1;
+                let mut ranges = vec![];
+                for group in self.element_groups(op.node) {
+                    if let Some(&element) = group.last().filter(|n| self.is_element(**n)) {
+                        let end = element_end(element)?.end;
+                        ranges.push((element, start..end));
+                        start = end;
+                    }
+                }
+                let range_of = |element: Node| {
+                    ranges
+                        .iter()
+                        .find(|(e, _)| *e == element)
+                        .map(|(_, range)| range.clone())
+                        .ok_or(Error::SpanNotFound)
+                };
+                let mut sorted = op.elements.iter();
+                let mut patches = vec![];
+                for (element, range) in &ranges {
+                    if op.elements.contains(element) {
+                        let next = *sorted.next().ok_or(Error::NotAnElement)?;
+                        if next != *element {
+                            patches.push(Patch::copy(range.clone(), range_of(next)?));
+                        }
+                    }
+                }
+                patches
+            }
+            Operation::DeleteNode(op) | Operation::DeleteDuplicate(op) => delete_patches(
+                self,
+                source,
+                span_info,
+                op.node,
+                self.is_only_child(op.node),
+            )?,
+        };
+
+        Ok(patches)
+    }
+
+    fn patch_all(
+        &self,
+        source: &str,
+        span_info: &SpanInfo,
+        operations: &[Operation],
+    ) -> Result<String, Error> {
+        let deleted: Vec<Node> = operations
+            .iter()
+            .filter_map(|operation| match operation {
+                Operation::DeleteNode(op) | Operation::DeleteDuplicate(op) => Some(op.node),
+                _ => None,
+            })
+            .collect();
+        let filled: Vec<Node> = operations
+            .iter()
+            .filter_map(|operation| filled_element(self, operation))
+            .collect();
+        // deleting all children of a parent empties it, like deleting them
+        // one after another in the tree does, unless something else goes
+        // into the parent
+        let empties_parent = |node: Node| {
+            self.parent(node).is_some_and(|parent| {
+                !filled.contains(&parent)
+                    && self.children(parent).all(|n| {
+                        deleted.contains(&n)
+                            || self.text_str(n).is_some_and(|t| t.trim().is_empty())
+                    })
+            })
+        };
+        let mut patches: Vec<Patch> = vec![];
+        for operation in operations {
+            let operation_patches = match operation {
+                Operation::DeleteNode(op) | Operation::DeleteDuplicate(op) => {
+                    delete_patches(self, source, span_info, op.node, empties_parent(op.node))?
+                }
+                _ => self.get_patches(source, span_info, operation)?,
+            };
+            for patch in operation_patches {
+                // the same range replaced twice: the later operation wins,
+                // the same insertion twice (element expansion) is kept once.
+                // A removal inside of a sorted range goes with its copy.
+                if !patch.range.is_empty() {
+                    patches.retain(|p| p.range != patch.range || p.from != patch.from);
+                } else if patch.closing && patches.contains(&patch) {
+                    continue;
+                }
+                patches.push(patch);
+            }
+        }
+        // stable sort keeps operation order for insertions at the same spot,
+        // a range starting there is removed or replaced after them and a
+        // copy of it goes first
+        patches.sort_by_key(|p| {
+            (
+                p.range.start,
+                !p.range.is_empty(),
+                p.closing,
+                p.from.is_none(),
+            )
+        });
+
+        render(source, 0..source.len(), &patches)
+    }
+}
@@ -0,0 +1,57 @@
+use crate::patchable::text_range;
+use xot::{Node, SpanInfo, SpanInfoKey, Xot};
+
+/// Position of a node in the source text. Lines and columns start at 1,
+/// columns count characters and the end column is the one after the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Region {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+pub trait Locatable {
+    /// Region of an element from `<` of its start tag to the end of its end tag.
+    /// The tree must be parsed with [`Xot::parse_with_span_info`].
+    fn region(&self, source: &str, span_info: &SpanInfo, node: Node) -> Option<Region>;
+    /// Text nodes in the node that are written with CDATA sections in the
+    /// source, in whole or in part
+    fn cdata_nodes(&self, source: &str, span_info: &SpanInfo, node: Node) -> Vec<Node>;
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+impl Locatable for Xot {
+    fn region(&self, source: &str, span_info: &SpanInfo, node: Node) -> Option<Region> {
+        // element name span starts right after `<`
+        let start = span_info.get(SpanInfoKey::ElementStart(node))?.start - 1;
+        let end = span_info.get(SpanInfoKey::ElementEnd(node))?.end;
+        let (start_line, start_column) = line_column(source, start);
+        let (end_line, end_column) = line_column(source, end);
+        Some(Region {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        })
+    }
+
+    fn cdata_nodes(&self, source: &str, span_info: &SpanInfo, node: Node) -> Vec<Node> {
+        self.descendants(node)
+            .filter(|n| self.is_text(*n))
+            .filter(|n| {
+                span_info
+                    .get(SpanInfoKey::Text(*n))
+                    .is_some_and(|s| source[text_range(source, s.range())].contains("<![CDATA["))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,100 @@
+use crate::prelude::Error;
+use std::collections::HashMap;
+use xml_mut_data::{
+    Bindings, Mutation, Predicate, PredicateDefinition, Statement, WhereClause, XmlMutGrammar,
+};
+
+/// Mutations of a grammar ready to be evaluated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved<'a> {
+    /// Mutations in statement order, predicate references replaced by the
+    /// predicates they name
+    pub mutations: Vec<Mutation<'a>>,
+    /// `LET` values, overridden by the values passed to [`resolve`]
+    pub bindings: Bindings,
+}
+
+fn inline_predicates<'a>(
+    predicates: &[Predicate<'a>],
+    definitions: &HashMap<&str, &PredicateDefinition<'a>>,
+    visiting: &mut Vec<&'a str>,
+) -> Result<Vec<Predicate<'a>>, Error> {
+    let mut inlined = vec![];
+    for predicate in predicates {
+        match predicate {
+            Predicate::Reference(name) => {
+                if visiting.contains(name) {
+                    return Err(Error::PredicateCycle(name.to_string()));
+                }
+                let definition = definitions
+                    .get(name)
+                    .ok_or(Error::PredicateNotFound(name.to_string()))?;
+                // predicates are joined with `and`, a reference is the same
+                // as writing its predicates in place
+                visiting.push(name);
+                inlined.append(&mut inline_predicates(
+                    &definition.predicates,
+                    definitions,
+                    visiting,
+                )?);
+                visiting.pop();
+            }
+            predicate => inlined.push(predicate.clone()),
+        }
+    }
+    Ok(inlined)
+}
+
+/// Resolution pass run before evaluation: binds `LET` values and replaces
+/// references to `DEFINE PREDICATE` statements.
+pub fn resolve<'a>(grammar: &XmlMutGrammar<'a>, defines: &Bindings) -> Result<Resolved<'a>, Error> {
+    let mut bindings = Bindings::default();
+    let mut definitions = HashMap::new();
+    for statement in &grammar.statements {
+        match statement {
+            Statement::Let(l) => bindings.insert(l.name, l.value),
+            Statement::DefinePredicate(d) => {
+                definitions.insert(d.name, d);
+            }
+            Statement::Mutation(_) | Statement::Comment(_) | Statement::Include(_) => (),
+        }
+    }
+    bindings.extend(defines);
+
+    let mut mutations = vec![];
+    for statement in &grammar.statements {
+        if let Statement::Mutation(mutation) = statement {
+            let mut mutation = mutation.as_ref().clone();
+            if let Some(where_clause) = &mutation.where_clause {
+                let predicates =
+                    inline_predicates(&where_clause.predicates, &definitions, &mut vec![])?;
+                mutation.where_clause = Some(WhereClause {
+                    predicates,
+                    ..where_clause.clone()
+                });
+            }
+            if let Some(where_clause) = mutation
+                .move_clause
+                .as_mut()
+                .and_then(|m| m.where_clause.as_mut())
+            {
+                where_clause.predicates =
+                    inline_predicates(&where_clause.predicates, &definitions, &mut vec![])?;
+            }
+            if let Some(delete_clause) = &mut mutation.delete_clause {
+                for target in &mut delete_clause.targets {
+                    if let Some(where_clause) = &mut target.where_clause {
+                        where_clause.predicates =
+                            inline_predicates(&where_clause.predicates, &definitions, &mut vec![])?;
+                    }
+                }
+            }
+            mutations.push(mutation);
+        }
+    }
+
+    Ok(Resolved {
+        mutations,
+        bindings,
+    })
+}
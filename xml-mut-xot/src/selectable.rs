@@ -0,0 +1,69 @@
+use crate::prelude::{Fitable, NodeExtensions, Valueable};
+use xml_mut_data::{Bindings, Mutation, SelectClause};
+use xot::{Node, Xot};
+
+/// Values projected out of a single matched node by a `SELECT` clause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub node: Node,
+    /// Selected path as written in the query and its value,
+    /// `None` when the node has no such value
+    pub values: Vec<(String, Option<String>)>,
+}
+
+pub trait Selectable {
+    fn select(&self, node: Node, select_clause: &SelectClause) -> Selection;
+    fn select_all(
+        &self,
+        node: Node,
+        mutations: &[&Mutation],
+        bindings: &Bindings,
+    ) -> Vec<Selection>;
+    /// Names of the node and its ancestors joined by `/`, e.g. `Project/ItemGroup`
+    fn element_path(&self, node: Node) -> String;
+}
+
+impl Selectable for Xot {
+    fn select(&self, node: Node, select_clause: &SelectClause) -> Selection {
+        let values = select_clause
+            .values
+            .iter()
+            .map(|path| {
+                let value = self.get_child_value(node, path).map(|v| v.to_string());
+                (path.to_string(), value)
+            })
+            .collect();
+        Selection { node, values }
+    }
+
+    fn select_all(
+        &self,
+        node: Node,
+        mutations: &[&Mutation],
+        bindings: &Bindings,
+    ) -> Vec<Selection> {
+        let mut selections = vec![];
+        for &mutation in mutations {
+            let select_clause = match &mutation.select_clause {
+                Some(select_clause) => select_clause,
+                None => continue,
+            };
+            for node in self.descendants(node) {
+                if self.is_fit(node, mutation, bindings) {
+                    selections.push(self.select(node, select_clause));
+                }
+            }
+        }
+        selections
+    }
+
+    fn element_path(&self, node: Node) -> String {
+        // ancestors start with the node itself
+        let mut names: Vec<&str> = self
+            .ancestors(node)
+            .filter_map(|n| self.get_name(n))
+            .collect();
+        names.reverse();
+        names.join("/")
+    }
+}
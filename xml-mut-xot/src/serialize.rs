@@ -0,0 +1,383 @@
+use crate::{
+    node_ext::{escape_attribute, escape_text, is_blank},
+    prelude::{cdata_section, Error, NodeExtensions},
+};
+use std::collections::BTreeMap;
+use xot::{
+    output::{NoopNormalizer, Output},
+    Node, Value, Xot,
+};
+
+/// How an xml tree is turned back into text.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SerializeOptions {
+    /// xot serialization, whitespace is written as it is in the tree
+    #[default]
+    Plain,
+    /// Indented output, whitespace only text in element content is dropped
+    Pretty(PrettyOptions),
+    /// Canonical XML 1.0 or Exclusive XML Canonicalization 1.0
+    Canonical(CanonicalOptions),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// Text used for one level of indentation
+    pub indent: String,
+    /// Elements with more attributes than this get one attribute per line,
+    /// `None` keeps all attributes on the element line
+    pub attributes_per_line: Option<usize>,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            indent: "  ".to_string(),
+            attributes_per_line: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CanonicalOptions {
+    /// Exclusive canonicalization, namespaces are declared where they are used
+    pub exclusive: bool,
+    /// Keep comments, canonical xml drops them by default
+    pub with_comments: bool,
+}
+
+pub trait Serializable {
+    fn serialize_with(&self, node: Node, options: &SerializeOptions) -> Result<String, Error> {
+        self.serialize_with_cdata(node, options, &[])
+    }
+    /// `cdata` text nodes are written as CDATA sections, canonical xml has
+    /// none
+    fn serialize_with_cdata(
+        &self,
+        node: Node,
+        options: &SerializeOptions,
+        cdata: &[Node],
+    ) -> Result<String, Error>;
+    fn serialize_pretty(&self, node: Node, options: &PrettyOptions) -> Result<String, Error> {
+        self.serialize_pretty_with_cdata(node, options, &[])
+    }
+    fn serialize_pretty_with_cdata(
+        &self,
+        node: Node,
+        options: &PrettyOptions,
+        cdata: &[Node],
+    ) -> Result<String, Error>;
+    fn serialize_canonical(&self, node: Node, options: &CanonicalOptions) -> Result<String, Error>;
+}
+
+fn namespace_attribute(prefix: &str) -> String {
+    if prefix.is_empty() {
+        "xmlns".to_string()
+    } else {
+        format!("xmlns:{}", prefix)
+    }
+}
+
+fn prefix_of(full_name: &str) -> &str {
+    full_name.split_once(':').map(|(p, _)| p).unwrap_or("")
+}
+
+struct PrettyWriter<'a> {
+    xot: &'a Xot,
+    options: &'a PrettyOptions,
+    /// Text nodes written as CDATA sections
+    cdata: &'a [Node],
+    xml: String,
+}
+
+impl PrettyWriter<'_> {
+    fn indent(&mut self, level: usize) {
+        for _ in 0..level {
+            self.xml.push_str(&self.options.indent);
+        }
+    }
+
+    /// Writes a node on its own line(s), or inline when `inline` is set
+    fn node(&mut self, node: Node, level: usize, inline: bool) -> Result<(), Error> {
+        let xot = self.xot;
+        if !inline {
+            self.indent(level);
+        }
+        match xot.value(node) {
+            Value::Element(element) => {
+                let name = xot
+                    .full_name(node, element.name())
+                    .map_err(Error::XotError)?;
+                let mut attributes: Vec<String> = xot
+                    .namespaces(node)
+                    .iter()
+                    .map(|(prefix, ns)| {
+                        format!(
+                            "{}=\"{}\"",
+                            namespace_attribute(xot.prefix_str(prefix)),
+                            escape_attribute(xot.namespace_str(*ns), '"')
+                        )
+                    })
+                    .collect();
+                for (name_id, value) in xot.attributes(node).iter() {
+                    let name = xot.full_name(node, name_id).map_err(Error::XotError)?;
+                    attributes.push(format!("{}=\"{}\"", name, escape_attribute(value, '"')));
+                }
+
+                self.xml.push('<');
+                self.xml.push_str(&name);
+                let per_line = !inline
+                    && self
+                        .options
+                        .attributes_per_line
+                        .is_some_and(|max| attributes.len() > max);
+                for attribute in attributes {
+                    if per_line {
+                        self.xml.push('\n');
+                        self.indent(level + 1);
+                    } else {
+                        self.xml.push(' ');
+                    }
+                    self.xml.push_str(&attribute);
+                }
+
+                let children: Vec<Node> = xot.children(node).collect();
+                if children.is_empty() {
+                    self.xml.push_str("/>");
+                    return Ok(());
+                }
+                self.xml.push('>');
+
+                // mixed content is written as is, indenting it would
+                // change the text
+                let mixed = inline
+                    || xot.preserves_space(node)
+                    || children
+                        .iter()
+                        .any(|n| xot.text_str(*n).is_some_and(|t| !is_blank(t)));
+                if mixed {
+                    for child in children {
+                        self.node(child, level + 1, true)?;
+                    }
+                } else {
+                    for child in children.into_iter().filter(|n| !xot.is_text(*n)) {
+                        self.xml.push('\n');
+                        self.node(child, level + 1, false)?;
+                    }
+                    self.xml.push('\n');
+                    self.indent(level);
+                }
+                self.xml.push_str("</");
+                self.xml.push_str(&name);
+                self.xml.push('>');
+            }
+            Value::Text(text) if self.cdata.contains(&node) => {
+                self.xml.push_str(&cdata_section(text.get()))
+            }
+            Value::Text(text) => self.xml.push_str(&escape_text(text.get())),
+            Value::Comment(comment) => {
+                self.xml.push_str(&format!("<!--{}-->", comment.get()));
+            }
+            Value::ProcessingInstruction(pi) => {
+                let target = xot.local_name_str(pi.target());
+                match pi.data() {
+                    Some(data) => self.xml.push_str(&format!("<?{} {}?>", target, data)),
+                    None => self.xml.push_str(&format!("<?{}?>", target)),
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+struct CanonicalWriter<'a> {
+    xot: &'a Xot,
+    options: &'a CanonicalOptions,
+    xml: String,
+}
+
+impl CanonicalWriter<'_> {
+    /// `rendered` holds the namespace declarations in effect in the output
+    fn node(&mut self, node: Node, rendered: &BTreeMap<String, String>) -> Result<(), Error> {
+        let xot = self.xot;
+        match xot.value(node) {
+            Value::Element(element) => {
+                let name = xot
+                    .full_name(node, element.name())
+                    .map_err(Error::XotError)?;
+
+                let mut attributes = vec![];
+                for (name_id, value) in xot.attributes(node).iter() {
+                    let full_name = xot.full_name(node, name_id).map_err(Error::XotError)?;
+                    attributes.push((
+                        xot.uri_str(name_id).to_string(),
+                        xot.local_name_str(name_id).to_string(),
+                        full_name,
+                        value.clone(),
+                    ));
+                }
+                attributes.sort();
+
+                // candidate declarations: the ones on this element, or for
+                // exclusive canonicalization the prefixes it visibly uses
+                let mut declarations = BTreeMap::new();
+                if self.options.exclusive {
+                    let used = std::iter::once(prefix_of(&name)).chain(
+                        attributes
+                            .iter()
+                            .map(|(_, _, full_name, _)| prefix_of(full_name))
+                            .filter(|p| !p.is_empty()),
+                    );
+                    for prefix in used.filter(|p| *p != "xml") {
+                        let uri = xot
+                            .prefix(prefix)
+                            .and_then(|p| xot.namespace_for_prefix(node, p))
+                            .map(|ns| xot.namespace_str(ns).to_string())
+                            .unwrap_or_default();
+                        declarations.insert(prefix.to_string(), uri);
+                    }
+                } else {
+                    for (prefix, ns) in xot.namespaces(node).iter() {
+                        declarations.insert(
+                            xot.prefix_str(prefix).to_string(),
+                            xot.namespace_str(*ns).to_string(),
+                        );
+                    }
+                }
+
+                let mut in_effect = rendered.clone();
+                self.xml.push('<');
+                self.xml.push_str(&name);
+                for (prefix, uri) in declarations {
+                    let current = rendered.get(&prefix).map(String::as_str).unwrap_or("");
+                    if current == uri {
+                        continue;
+                    }
+                    self.xml.push_str(&format!(
+                        " {}=\"{}\"",
+                        namespace_attribute(&prefix),
+                        escape_attribute(&uri, '"')
+                    ));
+                    in_effect.insert(prefix, uri);
+                }
+                for (_, _, full_name, value) in attributes {
+                    self.xml.push_str(&format!(
+                        " {}=\"{}\"",
+                        full_name,
+                        escape_attribute(&value, '"')
+                    ));
+                }
+                self.xml.push('>');
+                for child in xot.children(node) {
+                    self.node(child, &in_effect)?;
+                }
+                self.xml.push_str(&format!("</{}>", name));
+            }
+            Value::Text(text) => self.xml.push_str(&escape_text(text.get())),
+            Value::Comment(comment) if self.options.with_comments => {
+                self.xml.push_str(&format!("<!--{}-->", comment.get()));
+            }
+            Value::ProcessingInstruction(pi) => {
+                let target = xot.local_name_str(pi.target());
+                match pi.data() {
+                    Some(data) => self.xml.push_str(&format!("<?{} {}?>", target, data)),
+                    None => self.xml.push_str(&format!("<?{}?>", target)),
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+impl Serializable for Xot {
+    fn serialize_with_cdata(
+        &self,
+        node: Node,
+        options: &SerializeOptions,
+        cdata: &[Node],
+    ) -> Result<String, Error> {
+        match options {
+            SerializeOptions::Plain if cdata.is_empty() => self
+                .serialize_xml_string(Default::default(), node)
+                .map_err(Error::XotError),
+            SerializeOptions::Plain => {
+                // xot writes cdata for whole element names, so the text
+                // tokens of the cdata nodes are replaced instead
+                let mut xml = String::new();
+                for (token_node, output, token) in
+                    self.tokens(node, Default::default(), NoopNormalizer)
+                {
+                    if token.space {
+                        xml.push(' ');
+                    }
+                    match output {
+                        Output::Text(text) if cdata.contains(&token_node) => {
+                            xml.push_str(&cdata_section(text))
+                        }
+                        _ => xml.push_str(&token.text),
+                    }
+                }
+                Ok(xml)
+            }
+            SerializeOptions::Pretty(o) => self.serialize_pretty_with_cdata(node, o, cdata),
+            SerializeOptions::Canonical(o) => self.serialize_canonical(node, o),
+        }
+    }
+
+    fn serialize_pretty_with_cdata(
+        &self,
+        node: Node,
+        options: &PrettyOptions,
+        cdata: &[Node],
+    ) -> Result<String, Error> {
+        let mut writer = PrettyWriter {
+            xot: self,
+            options,
+            cdata,
+            xml: String::new(),
+        };
+        if self.is_document(node) {
+            for child in self.children(node).filter(|n| !self.is_text(*n)) {
+                writer.node(child, 0, false)?;
+                writer.xml.push('\n');
+            }
+        } else {
+            writer.node(node, 0, false)?;
+        }
+        Ok(writer.xml)
+    }
+
+    fn serialize_canonical(&self, node: Node, options: &CanonicalOptions) -> Result<String, Error> {
+        let mut writer = CanonicalWriter {
+            xot: self,
+            options,
+            xml: String::new(),
+        };
+        let rendered = BTreeMap::new();
+        if self.is_document(node) {
+            // nodes around the document element are separated by a newline
+            let document_element = self.document_element(node).map_err(Error::XotError)?;
+            let mut after = false;
+            for child in self.children(node).filter(|n| !self.is_text(*n)) {
+                if self.is_comment(child) && !options.with_comments {
+                    continue;
+                }
+                if child == document_element {
+                    writer.node(child, &rendered)?;
+                    after = true;
+                } else if after {
+                    writer.xml.push('\n');
+                    writer.node(child, &rendered)?;
+                } else {
+                    writer.node(child, &rendered)?;
+                    writer.xml.push('\n');
+                }
+            }
+        } else {
+            writer.node(node, &rendered)?;
+        }
+        Ok(writer.xml)
+    }
+}
@@ -1,54 +1,524 @@
 use crate::{
+    node_ext::escape_text,
     operation::*,
     prelude::{Error, Fitable, NodeExtensions},
 };
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{hash_map::Entry, HashMap},
+};
 use xml_mut_data::{
-    Mutation, PathVariant, ValueAssignment, ValuePath, ValueSelector, ValueVariant,
+    Bindings, DeduplicateClause, DeduplicateKeep, DeleteTarget, ElementTemplate, InsertClause,
+    InsertPosition, MoveClause, Mutation, PathVariant, Predicate, Rename, SortClause,
+    ValueAssignment, ValuePath, ValueSelector, ValueVariant, WrapClause,
 };
 use xot::{Node, Xot};
 
 pub trait Valueable {
-    fn get_value(&self, node: Node, selector: &ValueSelector) -> Option<&str>;
-    fn get_child_value(&self, node: Node, path: &ValuePath) -> Option<&str>;
-    fn get_value_of<'a>(&'a self, node: Node, variant: &'a ValueVariant) -> Option<&'a str> {
+    /// Xml selectors serialize the node, other values are borrowed
+    fn get_value(&self, node: Node, selector: &ValueSelector) -> Option<Cow<'_, str>>;
+    fn get_child_value(&self, node: Node, path: &ValuePath) -> Option<Cow<'_, str>>;
+    /// `bindings` hold the values of `$name` parameters
+    fn get_value_of<'a>(
+        &'a self,
+        node: Node,
+        variant: &'a ValueVariant,
+        bindings: &'a Bindings,
+    ) -> Option<Cow<'a, str>> {
         match variant {
             ValueVariant::Selector(path) => self.get_child_value(node, path),
-            ValueVariant::LiteralString(val) => Some(val),
+            ValueVariant::LiteralString(val) | ValueVariant::Xml(val) => Some(Cow::Borrowed(val)),
+            ValueVariant::Parameter(name) => bindings.get(name).map(Cow::Borrowed),
         }
     }
     fn add_sub_tree(&self, node: Node, path: &ValuePath, value: String)
         -> Result<Operation, Error>;
-    fn assign(&self, node: Node, assignment: &ValueAssignment) -> Result<Operation, Error>;
-    fn delete(&self, node: Node, path_variant: &PathVariant) -> Result<Operation, Error>;
-    fn get_operations(&self, node: Node, mutation: &Mutation) -> Result<Vec<Operation>, Error>;
+    fn assign(
+        &self,
+        node: Node,
+        assignment: &ValueAssignment,
+        bindings: &Bindings,
+    ) -> Result<Vec<Operation>, Error>;
+    fn insert(
+        &self,
+        node: Node,
+        insert: &InsertClause,
+        bindings: &Bindings,
+    ) -> Result<Operation, Error>;
+    /// `None` when there is nothing to rename
+    fn rename(&self, node: Node, rename: &Rename) -> Option<Operation>;
+    /// Moved nodes go to the only node at the position path that fits the
+    /// clause `WHERE`, copies to every one of them
+    fn relocate(
+        &self,
+        node: Node,
+        move_clause: &MoveClause,
+        bindings: &Bindings,
+    ) -> Result<Vec<Operation>, Error>;
+    fn wrap_node(
+        &self,
+        node: Node,
+        wrap_clause: &WrapClause,
+        bindings: &Bindings,
+    ) -> Result<Operation, Error>;
+    fn unwrap_node(&self, node: Node) -> Result<Operation, Error>;
+    /// Children that are already in order are not sorted again, `removed`
+    /// children are left out
+    fn sort(&self, node: Node, sort_clause: &SortClause, removed: &[Node]) -> Vec<Operation>;
+    fn deduplicate(&self, node: Node, deduplicate_clause: &DeduplicateClause) -> Vec<Operation>;
+    /// `removed` nodes are taken out by earlier operations, they are not
+    /// deleted again and do not count when an `EMPTY` target is checked
+    fn delete(
+        &self,
+        node: Node,
+        target: &DeleteTarget,
+        bindings: &Bindings,
+        removed: &[Node],
+    ) -> Result<Vec<Operation>, Error>;
+    fn get_operations(
+        &self,
+        node: Node,
+        mutation: &Mutation,
+        bindings: &Bindings,
+        removed: &[Node],
+    ) -> Result<Vec<Operation>, Error>;
     fn get_operations_all(
         &self,
         node: Node,
         mutations: &[&Mutation],
+        bindings: &Bindings,
+    ) -> Result<Vec<Operation>, Error> {
+        self.get_operations_after(node, mutations, bindings, &[])
+    }
+    /// Operations of the mutations applied after the `earlier` operations,
+    /// nodes those take out of the document are not matched
+    fn get_operations_after(
+        &self,
+        node: Node,
+        mutations: &[&Mutation],
+        bindings: &Bindings,
+        earlier: &[Operation],
     ) -> Result<Vec<Operation>, Error>;
+    /// Whether one of the operations takes the node, or an ancestor of it,
+    /// out of the document
+    fn is_dropped(&self, node: Node, operations: &[Operation]) -> bool;
+    /// Removes the whitespace run that belongs to the node when it is alone
+    /// on its line: the line break and indentation in front of it
+    fn remove_own_whitespace(&mut self, node: Node) -> Result<(), Error>;
     fn apply(&mut self, operation: &Operation) -> Result<(), Error>;
     fn apply_all(&mut self, operations: &[Operation]) -> Result<(), Error>;
 }
 
-impl Valueable for Xot {
-    fn get_value(&self, node: Node, selector: &ValueSelector) -> Option<&str> {
-        match selector {
-            ValueSelector::Attribute(name) => Some(self.attributes(node).get(self.name(name)?)?),
-            ValueSelector::Text => self.first_child(node).and_then(|t| self.text_str(t)),
-            ValueSelector::Tail => self.next_sibling(node).and_then(|t| self.text_str(t)),
-            ValueSelector::Name => self.get_name(node),
+/// Compares dotted identifiers one by one, numbers by their value and
+/// lower than other identifiers. Of equal identifiers more of them is higher.
+fn compare_identifiers(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                _ => x.cmp(y),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Release and prerelease part of a version, without build metadata
+fn version_parts(version: &str) -> (&str, Option<&str>) {
+    let version = version.trim();
+    let version = version.split_once('+').map_or(version, |(v, _)| v);
+    match version.split_once('-') {
+        Some((release, prerelease)) => (release, Some(prerelease)),
+        None => (version, None),
+    }
+}
+
+/// Compares versions the way semver orders them, e.g. `1.10.0` is higher
+/// than `1.9.2`, `1.0.1` is higher than `1.0` and a prerelease like
+/// `1.0.0-beta` is lower than `1.0.0`. Build metadata after `+` is ignored.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_release, a_prerelease) = version_parts(a);
+    let (b_release, b_prerelease) = version_parts(b);
+    compare_identifiers(a_release, b_release).then_with(|| match (a_prerelease, b_prerelease) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_identifiers(a, b),
+    })
+}
+
+/// `[text]` of the element set to the value, it goes into a single text
+/// child and the others are emptied so the text reads back as the value.
+/// Whitespace indenting child elements is kept.
+fn set_text(xot: &Xot, node: Node, value: String) -> Vec<Operation> {
+    let Some(text_node) = xot.text_node(node) else {
+        return vec![Operation::PrependText(OpPrependText { node, value })];
+    };
+    let indented = xot.children(node).any(|n| xot.is_element(n));
+    xot.children(node)
+        .filter(|n| *n != text_node)
+        .filter(|n| {
+            xot.text_str(*n)
+                .is_some_and(|t| !indented || !t.trim().is_empty())
+        })
+        .map(|node| {
+            Operation::SetText(OpSetText {
+                node,
+                value: String::new(),
+            })
+        })
+        .chain(std::iter::once(Operation::SetText(OpSetText {
+            node: text_node,
+            value,
+        })))
+        .collect()
+}
+
+// TODO: specialized  DeleteError and map it in call site
+/// Deletes the node or, with a selector, its value
+fn delete_at(
+    xot: &Xot,
+    node: Node,
+    maybe_source: Option<&ValueSelector>,
+) -> Result<Vec<Operation>, Error> {
+    let Some(source) = maybe_source else {
+        return Ok(vec![Operation::DeleteNode(OpDeleteNode { node })]);
+    };
+    let value = "".to_string();
+
+    let op = match source {
+        ValueSelector::Attribute(name) => {
+            let name = name.to_string();
+            Operation::RemoveAttribute(OpRemoveAttribute { name, node })
+        }
+        ValueSelector::Text => {
+            xot.text_node(node).ok_or(Error::DeleteNothing(
+                "Delete text node not found.".to_string(),
+            ))?;
+            return Ok(set_text(xot, node, value));
+        }
+        ValueSelector::Tail => {
+            let node = xot.tail_text_node(node).ok_or(Error::DeleteNothing(
+                "Delete tail text node not found.".to_string(),
+            ))?;
+            Operation::SetText(OpSetText { node, value })
+        }
+        ValueSelector::Name => Err(Error::DeleteNameIsInvalid)?,
+        ValueSelector::String | ValueSelector::Inner => {
+            Operation::SetInner(OpSetInner { node, xml: value })
+        }
+        ValueSelector::Outer => Operation::DeleteNode(OpDeleteNode { node }),
+        ValueSelector::Comment => {
+            let node = xot.comment_node(node).ok_or(Error::DeleteNothing(
+                "Delete comment not found.".to_string(),
+            ))?;
+            Operation::DeleteNode(OpDeleteNode { node })
+        }
+        ValueSelector::ProcessingInstruction(target) => {
+            let node =
+                xot.processing_instruction_node(node, target)
+                    .ok_or(Error::DeleteNothing(format!(
+                        "Delete processing instruction {} not found.",
+                        target
+                    )))?;
+            Operation::DeleteNode(OpDeleteNode { node })
+        }
+    };
+
+    Ok(vec![op])
+}
+
+/// Xml markup of a new comment or processing instruction
+fn node_xml(selector: &ValueSelector, value: &str) -> Result<String, Error> {
+    match selector {
+        ValueSelector::Comment if !value.contains("--") && !value.ends_with('-') => {
+            Ok(format!("<!-- {} -->", value))
+        }
+        ValueSelector::ProcessingInstruction(target) if value.is_empty() => {
+            Ok(format!("<?{}?>", target))
+        }
+        ValueSelector::ProcessingInstruction(target) if !value.contains("?>") => {
+            Ok(format!("<?{} {}?>", target, value))
+        }
+        _ => Err(Error::InvalidNodeValue(value.to_string())),
+    }
+}
+
+/// Top level nodes of parsed xml markup, not attached to any parent yet
+fn parse_xml(xot: &mut Xot, xml: &str) -> Result<Vec<Node>, Error> {
+    let fragment = xot
+        .parse_fragment(xml)
+        .map_err(|err| Error::XotError(err.into()))?;
+    Ok(xot.children(fragment).collect())
+}
+
+/// Value of an attribute or the text of an element template
+fn template_value(
+    xot: &Xot,
+    node: Node,
+    variant: &ValueVariant,
+    bindings: &Bindings,
+) -> Result<String, Error> {
+    xot.get_value_of(node, variant, bindings)
+        .map(Cow::into_owned)
+        .ok_or(Error::AssignmentSourceValueNotFound(format!(
+            "Node {:?} does not contain a value at {:?}.",
+            xot.get_name(node).unwrap_or("noname"),
+            variant
+        )))
+}
+
+fn template_attributes(
+    xot: &Xot,
+    node: Node,
+    template: &ElementTemplate,
+    bindings: &Bindings,
+) -> Result<Vec<(String, String)>, Error> {
+    template
+        .attributes
+        .iter()
+        .map(|(name, variant)| {
+            Ok((
+                name.to_string(),
+                template_value(xot, node, variant, bindings)?,
+            ))
+        })
+        .collect()
+}
+
+/// A detached element with the attributes
+fn new_element(xot: &mut Xot, name: &str, attributes: &[(String, String)]) -> Node {
+    let name_id = xot.add_name(name);
+    let element = xot.new_element(name_id);
+    for (name, value) in attributes {
+        let name_id = xot.add_name(name);
+        xot.attributes_mut(element).insert(name_id, value.clone());
+    }
+    element
+}
+
+/// Puts a new or an existing node at the position, on its own line when
+/// there is indentation
+fn place(
+    xot: &mut Xot,
+    node: Node,
+    position: Position,
+    indentation: Option<&Indentation>,
+) -> Result<(), Error> {
+    let line = indentation.map(|ind| format!("\n{}", ind.child));
+    match (position, indentation) {
+        (Position::FirstIn(parent), Some(_)) if xot.first_child(parent).is_some() => {
+            xot.prepend(parent, node).map_err(Error::XotError)?;
+            if let Some(line) = line {
+                let text = xot.new_text(&line);
+                xot.prepend(parent, text).map_err(Error::XotError)?;
+            }
+        }
+        (Position::FirstIn(parent), None) => {
+            xot.prepend(parent, node).map_err(Error::XotError)?;
+        }
+        // nothing to go before, same as appending
+        (Position::FirstIn(parent) | Position::LastIn(parent), Some(ind)) => {
+            append_indented(xot, parent, node, ind)?;
+        }
+        (Position::LastIn(parent), None) => {
+            xot.append(parent, node).map_err(Error::XotError)?;
+        }
+        (Position::Before(sibling), _) => {
+            xot.insert_before(sibling, node).map_err(Error::XotError)?;
+            if let Some(line) = line {
+                let text = xot.new_text(&line);
+                xot.insert_before(sibling, text).map_err(Error::XotError)?;
+            }
+        }
+        (Position::After(sibling), _) => {
+            xot.insert_after(sibling, node).map_err(Error::XotError)?;
+            if let Some(line) = line {
+                let text = xot.new_text(&line);
+                xot.insert_after(sibling, text).map_err(Error::XotError)?;
+            }
         }
     }
+    Ok(())
+}
 
-    fn get_child_value(&self, node: Node, path: &ValuePath) -> Option<&str> {
+/// Shifts the lines inside of the node from its current indentation to
+/// the indentation of its new place
+fn reindent(xot: &mut Xot, node: Node, indentation: &Indentation) -> Result<(), Error> {
+    let from = xot.line_indentation(node).map(str::to_string);
+    reindent_from(xot, node, from.as_deref(), &indentation.child)
+}
+
+/// Lines inside of the node indented with `from` are indented with `to`
+fn reindent_from(xot: &mut Xot, node: Node, from: Option<&str>, to: &str) -> Result<(), Error> {
+    let from = match from {
+        Some(from) if !xot.preserves_space(node) => format!("\n{}", from),
+        _ => return Ok(()),
+    };
+    let to = format!("\n{}", to);
+    let texts: Vec<Node> = xot.descendants(node).filter(|n| xot.is_text(*n)).collect();
+    for text in texts {
+        let value = xot.text_str(text).unwrap_or_default().replace(&from, &to);
+        xot.text_mut(text).ok_or(Error::NotATextNode)?.set(value);
+    }
+    Ok(())
+}
+
+/// Adds the node of a wrap to an earlier wrap with the same wrappers that
+/// ends right before it, `false` when there is none
+fn join_wrap(xot: &Xot, operations: &mut [Operation], wrap: &OpWrapNode) -> bool {
+    let Some(&node) = wrap.nodes.first() else {
+        return false;
+    };
+    let mut previous = xot.previous_sibling(node);
+    while let Some(text) =
+        previous.filter(|n| xot.text_str(*n).is_some_and(|t| t.trim().is_empty()))
+    {
+        previous = xot.previous_sibling(text);
+    }
+    let joined = operations
+        .iter_mut()
+        .rev()
+        .find_map(|operation| match operation {
+            Operation::WrapNode(op)
+                if op.nodes.last().copied() == previous
+                    && op.wrappers == wrap.wrappers
+                    && op.indentation == wrap.indentation =>
+            {
+                Some(op)
+            }
+            _ => None,
+        });
+    match joined {
+        Some(op) => {
+            op.nodes.extend(&wrap.nodes);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Puts the nodes one after another starting at the position
+fn place_all(
+    xot: &mut Xot,
+    nodes: &[Node],
+    position: Position,
+    indentation: Option<&Indentation>,
+) -> Result<(), Error> {
+    let mut position = position;
+    for &node in nodes {
+        place(xot, node, position, indentation)?;
+        position = Position::After(node);
+    }
+    Ok(())
+}
+
+/// Position relative to the target node and the parent the node goes into
+fn position_at(
+    xot: &Xot,
+    position: &InsertPosition,
+    target: Node,
+) -> Result<(Position, Node), Error> {
+    let (position, parent) = match position {
+        InsertPosition::First(_) => (Position::FirstIn(target), Some(target)),
+        InsertPosition::Last(_) => (Position::LastIn(target), Some(target)),
+        InsertPosition::Before(_) => (Position::Before(target), xot.parent(target)),
+        InsertPosition::After(_) => (Position::After(target), xot.parent(target)),
+    };
+    Ok((position, parent.ok_or(Error::NotAnElement)?))
+}
+
+/// Appends a child on its own line, replacing the whitespace before the
+/// end tag of the node
+fn append_indented(
+    xot: &mut Xot,
+    node: Node,
+    child: Node,
+    indentation: &Indentation,
+) -> Result<(), Error> {
+    if let Some(last) = xot.last_child(node) {
+        if xot.is_text(last) {
+            xot.remove(last).map_err(Error::XotError)?;
+        }
+    }
+    xot.append_text(node, &format!("\n{}", indentation.child))
+        .map_err(Error::XotError)?;
+    xot.append(node, child).map_err(Error::XotError)?;
+    xot.append_text(node, &format!("\n{}", indentation.closing))
+        .map_err(Error::XotError)
+}
+
+impl Valueable for Xot {
+    fn get_value(&self, node: Node, selector: &ValueSelector) -> Option<Cow<'_, str>> {
+        let value = match selector {
+            ValueSelector::Attribute(name) => self.attributes(node).get(self.name(name)?)?,
+            // split by comments or elements, an empty element has empty text
+            // and one with only other content has none
+            ValueSelector::Text => {
+                let mut texts = self.children(node).filter_map(|n| self.text_str(n));
+                match (texts.next(), texts.next()) {
+                    (None, _) if self.first_child(node).is_none() => "",
+                    (None, _) => return None,
+                    (Some(text), None) => text,
+                    (Some(first), Some(second)) => {
+                        let rest: String = texts.collect();
+                        return Some(Cow::Owned(format!("{}{}{}", first, second, rest)));
+                    }
+                }
+            }
+            ValueSelector::String => {
+                if self.first_child(node).is_some()
+                    && !self.descendants(node).any(|n| self.is_text(n))
+                {
+                    return None;
+                }
+                return Some(Cow::Owned(self.string_value(node)));
+            }
+            ValueSelector::Tail => self.next_sibling(node).and_then(|t| self.text_str(t))?,
+            ValueSelector::Name => self.get_name(node)?,
+            ValueSelector::Inner => {
+                let children = self
+                    .children(node)
+                    .map(|child| self.to_string(child))
+                    .collect::<Result<String, _>>();
+                return children.ok().map(Cow::Owned);
+            }
+            ValueSelector::Outer => return self.to_string(node).ok().map(Cow::Owned),
+            // the spaces around the content are layout
+            ValueSelector::Comment => self.comment_str(self.comment_node(node)?)?.trim(),
+            ValueSelector::ProcessingInstruction(target) => self
+                .processing_instruction(self.processing_instruction_node(node, target)?)?
+                .data()
+                .unwrap_or_default()
+                .trim(),
+        };
+        Some(Cow::Borrowed(value))
+    }
+
+    fn get_child_value(&self, node: Node, path: &ValuePath) -> Option<Cow<'_, str>> {
         self.find_first_child_element(node, &path.node_path)
             .and_then(|n| self.get_value(n, &path.selector))
     }
 
     // TODO: specialized  AssignError and map it in call site
-    fn assign(&self, node: Node, assignment: &ValueAssignment) -> Result<Operation, Error> {
+    fn assign(
+        &self,
+        node: Node,
+        assignment: &ValueAssignment,
+        bindings: &Bindings,
+    ) -> Result<Vec<Operation>, Error> {
         let value = self
-            .get_value_of(node, &assignment.source)
+            .get_value_of(node, &assignment.source, bindings)
             .ok_or(
                 // NOTE: there is no predicate ensuring attribute existance yet
                 Error::AssignmentSourceValueNotFound(format!(
@@ -58,13 +528,20 @@ impl Valueable for Xot {
                 )),
             )?
             .to_string();
+        // text assigned to markup is escaped, markup assigned to text is
+        // kept as it is written
+        let value = if assignment.target.selector.is_xml() && !assignment.source.is_xml() {
+            escape_text(&value)
+        } else {
+            value
+        };
 
         // TODO: instead of self.find_first_child_element
         // use enumerable and assign for all matches
         let node = match self.find_first_child_element(node, &assignment.target.node_path) {
             Some(n) => n,
             None => {
-                return self.add_sub_tree(node, &assignment.target, value);
+                return Ok(vec![self.add_sub_tree(node, &assignment.target, value)?]);
             }
         };
 
@@ -74,13 +551,12 @@ impl Valueable for Xot {
                 name: name.to_string(),
                 value,
             }),
-            ValueSelector::Text => {
-                if let Some(node) = self.text_node(node) {
-                    Operation::SetText(OpSetText { node, value })
-                } else {
-                    Operation::PrependText(OpPrependText { node, value })
-                }
-            }
+            ValueSelector::Text => return Ok(set_text(self, node, value)),
+            // the element content becomes the text
+            ValueSelector::String => Operation::SetInner(OpSetInner {
+                node,
+                xml: escape_text(&value),
+            }),
             ValueSelector::Tail => {
                 if let Some(node) = self.tail_text_node(node) {
                     Operation::SetText(OpSetText { node, value })
@@ -89,9 +565,44 @@ impl Valueable for Xot {
                 }
             }
             ValueSelector::Name => Operation::SetName(OpSetName { node, name: value }),
+            ValueSelector::Inner => Operation::SetInner(OpSetInner { node, xml: value }),
+            ValueSelector::Outer => Operation::ReplaceNode(OpReplaceNode { node, xml: value }),
+            ValueSelector::Comment | ValueSelector::ProcessingInstruction(_) => {
+                let xml = node_xml(&assignment.target.selector, &value)?;
+                let existing = match assignment.target.selector {
+                    ValueSelector::ProcessingInstruction(target) => {
+                        self.processing_instruction_node(node, target)
+                    }
+                    _ => self.comment_node(node),
+                };
+                match existing {
+                    Some(node) => Operation::SetNodeValue(OpSetNodeValue { node, value }),
+                    // instructions of the document go before the document element
+                    None if matches!(
+                        assignment.target.selector,
+                        ValueSelector::ProcessingInstruction(_)
+                    ) && self.is_document_element(node) =>
+                    {
+                        Operation::InsertNode(OpInsertNode {
+                            position: Position::Before(node),
+                            xml,
+                            indentation: self.line_indentation(node).map(|line| Indentation {
+                                child: line.to_string(),
+                                closing: line.to_string(),
+                                unit: String::new(),
+                            }),
+                        })
+                    }
+                    None => Operation::InsertNode(OpInsertNode {
+                        position: Position::FirstIn(node),
+                        xml,
+                        indentation: self.child_indentation(node),
+                    }),
+                }
+            }
         };
 
-        Ok(op)
+        Ok(vec![op])
     }
 
     fn add_sub_tree(
@@ -125,100 +636,441 @@ impl Valueable for Xot {
         if ValueSelector::Name == path.selector && path_vec.pop().is_some() {
             path_vec.push(value.as_str());
         }
+        // the markup takes the place of the last element
+        if ValueSelector::Outer == path.selector {
+            path_vec.pop();
+        }
 
         let node_path: Vec<String> = path_vec.iter().map(|s| s.to_string()).collect();
 
         let sub_op = match path.selector {
             ValueSelector::Attribute(name) => SubOperation::AddAttribute(name.to_string(), value),
-            ValueSelector::Text => SubOperation::AddText(value),
+            ValueSelector::Text | ValueSelector::String => SubOperation::AddText(value),
             ValueSelector::Tail => SubOperation::AddTailText(value),
             ValueSelector::Name => SubOperation::None,
+            ValueSelector::Inner | ValueSelector::Outer => SubOperation::AddXml(value),
+            ValueSelector::Comment | ValueSelector::ProcessingInstruction(_) => {
+                SubOperation::AddXml(node_xml(&path.selector, &value)?)
+            }
+        };
+
+        let indentation = if node_path.is_empty() {
+            None
+        } else {
+            self.child_indentation(node)
         };
 
         Ok(Operation::AddSubTree(OpAddSubTree {
             node,
             node_path,
             sub_op,
+            indentation,
         }))
     }
 
-    // TODO: specialized  DeleteError and map it in call site
-    fn delete(&self, node: Node, path_variant: &PathVariant) -> Result<Operation, Error> {
-        let (path, maybe_source) = match path_variant {
-            PathVariant::Value(v) => (&v.node_path, Some(&v.selector)),
-            PathVariant::Node(p) => (p, None),
-        };
+    fn insert(
+        &self,
+        node: Node,
+        insert: &InsertClause,
+        bindings: &Bindings,
+    ) -> Result<Operation, Error> {
+        let attributes = template_attributes(self, node, &insert.element, bindings)?;
+        let text = insert
+            .element
+            .text
+            .as_ref()
+            .map(|variant| template_value(self, node, variant, bindings))
+            .transpose()?;
 
-        let node = self
+        let path = insert.position.node_path();
+        let target = self
             .find_first_child_element(node, path)
-            .ok_or(Error::DeleteNothing(
-                "Delete target node not found.".to_string(),
-            ))?;
+            .ok_or(Error::InsertTargetNotFound(path.to_string()))?;
+        let (position, parent) = position_at(self, &insert.position, target)?;
 
-        if let Some(source) = maybe_source {
-            if *source == ValueSelector::Name {
-                Err(Error::DeleteNameIsInvalid)?;
+        Ok(Operation::InsertElement(OpInsertElement {
+            position,
+            name: insert.element.name.to_string(),
+            attributes,
+            text,
+            indentation: self.child_indentation(parent),
+        }))
+    }
+
+    fn rename(&self, node: Node, rename: &Rename) -> Option<Operation> {
+        let new_name = rename.name.to_string();
+        match &rename.source {
+            PathVariant::Value(ValuePath {
+                node_path,
+                selector: ValueSelector::Attribute(name),
+            }) => {
+                let node = self.find_first_child_element(node, node_path)?;
+                self.attributes(node).get(self.name(name)?)?;
+                Some(Operation::RenameAttribute(OpRenameAttribute {
+                    node,
+                    name: name.to_string(),
+                    new_name,
+                }))
+            }
+            // the parser only allows attributes
+            PathVariant::Value(_) => None,
+            PathVariant::Node(path) => {
+                let node = self.find_first_child_element(node, path)?;
+                Some(Operation::SetName(OpSetName {
+                    node,
+                    name: new_name,
+                }))
             }
+        }
+    }
 
-            let value = "".to_string();
+    fn relocate(
+        &self,
+        node: Node,
+        move_clause: &MoveClause,
+        bindings: &Bindings,
+    ) -> Result<Vec<Operation>, Error> {
+        let nodes = self.find_child_elements(node, &move_clause.source);
+        let path = move_clause.position.node_path();
+        let predicates: &[Predicate] = match &move_clause.where_clause {
+            Some(where_clause) => &where_clause.predicates,
+            None => &[],
+        };
+        let fits = |target: &Node| {
+            predicates.iter().all(|p| match p.relative_to(path) {
+                Some(relative) => self.fits_predicate(*target, &relative, bindings),
+                None => self.fits_predicate(node, p, bindings),
+            })
+        };
+        let targets: Vec<Node> = self
+            .find_child_elements(node, path)
+            .into_iter()
+            .filter(fits)
+            .collect();
+        if targets.is_empty() {
+            Err(Error::InsertTargetNotFound(path.to_string()))?;
+        }
+        if !move_clause.copy {
+            if targets.len() > 1 {
+                Err(Error::MoveTargetAmbiguous(path.to_string()))?;
+            }
+            if self
+                .ancestors(targets[0])
+                .any(|ancestor| nodes.contains(&ancestor))
+            {
+                Err(Error::MoveIntoItself(path.to_string()))?;
+            }
+        }
 
-            let op = match source {
-                ValueSelector::Attribute(name) => {
-                    let name = name.to_string();
-                    Operation::RemoveAttribute(OpRemoveAttribute { name, node })
-                }
-                ValueSelector::Text => {
-                    let node = self.text_node(node).ok_or(Error::DeleteNothing(
-                        "Delete text node not found.".to_string(),
-                    ))?;
-                    Operation::SetText(OpSetText { node, value })
+        targets
+            .into_iter()
+            .map(|target| {
+                let (position, parent) = position_at(self, &move_clause.position, target)?;
+                let nodes = nodes.clone();
+                let indentation = self.child_indentation(parent);
+                Ok(if move_clause.copy {
+                    Operation::CopyNodes(OpCopyNodes {
+                        nodes,
+                        position,
+                        indentation,
+                    })
+                } else {
+                    Operation::MoveNodes(OpMoveNodes {
+                        nodes,
+                        position,
+                        indentation,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    fn wrap_node(
+        &self,
+        node: Node,
+        wrap_clause: &WrapClause,
+        bindings: &Bindings,
+    ) -> Result<Operation, Error> {
+        let wrappers = wrap_clause
+            .wrappers
+            .iter()
+            .map(|wrapper| {
+                let attributes = template_attributes(self, node, wrapper, bindings)?;
+                Ok((wrapper.name.to_string(), attributes))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        // only a node on its own line gets its wrappers on their own lines
+        let indentation = self
+            .line_indentation(node)
+            .filter(|_| !self.preserves_space(node))
+            .zip(self.parent(node).and_then(|p| self.child_indentation(p)))
+            .map(|(line, ind)| Indentation {
+                child: line.to_string(),
+                closing: line.to_string(),
+                unit: ind.unit,
+            });
+
+        Ok(Operation::WrapNode(OpWrapNode {
+            nodes: vec![node],
+            wrappers,
+            indentation,
+        }))
+    }
+
+    fn unwrap_node(&self, node: Node) -> Result<Operation, Error> {
+        if self.is_document_element(node) {
+            Err(Error::UnwrapDocumentElement)?;
+        }
+        if self
+            .children(node)
+            .all(|n| self.text_str(n).is_some_and(|t| t.trim().is_empty()))
+        {
+            // nothing to keep
+            return Ok(Operation::DeleteNode(OpDeleteNode { node }));
+        }
+        // the whitespace lines around mixed content go with the tags too
+        let text = |n: Option<Node>| n.and_then(|n| self.text_str(n));
+        let leading = text(self.first_child(node)).map(|t| &t[..t.len() - t.trim_start().len()]);
+        let trailing = text(self.last_child(node)).map(|t| &t[t.trim_end().len()..]);
+        let indentation = match (leading, trailing) {
+            (Some(leading), Some(trailing))
+                if leading.contains('\n')
+                    && trailing.contains('\n')
+                    && !self.preserves_space(node) =>
+            {
+                let child = leading.rsplit('\n').next().unwrap_or_default();
+                let closing = trailing.rsplit('\n').next().unwrap_or_default();
+                Some(Indentation {
+                    child: child.to_string(),
+                    closing: closing.to_string(),
+                    unit: child.strip_prefix(closing).unwrap_or_default().to_string(),
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Operation::UnwrapNode(OpUnwrapNode { node, indentation }))
+    }
+
+    fn sort(&self, node: Node, sort_clause: &SortClause, removed: &[Node]) -> Vec<Operation> {
+        let Some((name, parent_path)) = sort_clause.path.split_last() else {
+            return vec![];
+        };
+        let key = |element: &Node| {
+            self.get_child_value(*element, &sort_clause.key)
+                .map(|value| match sort_clause.case_insensitive {
+                    true => value.to_lowercase(),
+                    false => value.into_owned(),
+                })
+        };
+
+        self.find_child_elements(node, parent_path)
+            .into_iter()
+            .filter_map(|parent| {
+                let elements: Vec<Node> = self
+                    .children(parent)
+                    .filter(|n| self.is_element_with_name(*n, name) && !removed.contains(n))
+                    .collect();
+                let mut sorted = elements.clone();
+                // stable, equal keys keep their order
+                if sort_clause.descending {
+                    sorted.sort_by_cached_key(|n| std::cmp::Reverse(key(n)));
+                } else {
+                    sorted.sort_by_cached_key(key);
                 }
-                ValueSelector::Tail => {
-                    let node = self.tail_text_node(node).ok_or(Error::DeleteNothing(
-                        "Delete tail text node not found.".to_string(),
-                    ))?;
-                    Operation::SetText(OpSetText { node, value })
+                (sorted != elements).then_some(Operation::SortChildren(OpSortChildren {
+                    node: parent,
+                    elements: sorted,
+                }))
+            })
+            .collect()
+    }
+
+    fn deduplicate(&self, node: Node, deduplicate_clause: &DeduplicateClause) -> Vec<Operation> {
+        let Some((name, parent_path)) = deduplicate_clause.path.split_last() else {
+            return vec![];
+        };
+        // whether the element is kept instead of the one kept so far
+        let replaces = |kept: Node, element: Node| match &deduplicate_clause.keep {
+            DeduplicateKeep::First => false,
+            DeduplicateKeep::Last => true,
+            DeduplicateKeep::Highest(version) => {
+                match (
+                    self.get_child_value(element, version),
+                    self.get_child_value(kept, version),
+                ) {
+                    (Some(new), Some(old)) => compare_versions(&new, &old) == Ordering::Greater,
+                    (new, old) => new.is_some() && old.is_none(),
                 }
-                ValueSelector::Name => {
-                    unreachable!("already checked and returned Error::DeleteNameIsInvalid")
+            }
+        };
+
+        self.find_child_elements(node, parent_path)
+            .into_iter()
+            .flat_map(|parent| {
+                let elements: Vec<(Node, Option<Cow<str>>)> = self
+                    .children(parent)
+                    .filter(|n| self.is_element_with_name(*n, name))
+                    .map(|n| (n, self.get_child_value(n, &deduplicate_clause.key)))
+                    .collect();
+                let mut kept: HashMap<&str, Node> = HashMap::new();
+                for (element, key) in &elements {
+                    if let Some(key) = key {
+                        match kept.entry(key) {
+                            Entry::Vacant(entry) => {
+                                entry.insert(*element);
+                            }
+                            Entry::Occupied(mut entry) => {
+                                if replaces(*entry.get(), *element) {
+                                    entry.insert(*element);
+                                }
+                            }
+                        }
+                    }
                 }
-            };
+                elements
+                    .iter()
+                    .filter(|(element, key)| {
+                        key.as_ref()
+                            .is_some_and(|key| kept[key.as_ref()] != *element)
+                    })
+                    .map(|(node, _)| Operation::DeleteDuplicate(OpDeleteNode { node: *node }))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn delete(
+        &self,
+        node: Node,
+        target: &DeleteTarget,
+        bindings: &Bindings,
+        removed: &[Node],
+    ) -> Result<Vec<Operation>, Error> {
+        let (path, maybe_source) = match &target.path {
+            PathVariant::Value(v) => (&v.node_path, Some(&v.selector)),
+            PathVariant::Node(p) => (p, None),
+        };
+        if maybe_source == Some(&ValueSelector::Name) {
+            Err(Error::DeleteNameIsInvalid)?;
+        }
 
-            return Ok(op);
+        if !target.is_conditional() {
+            let node = self
+                .find_first_child_element(node, path)
+                .ok_or(Error::DeleteNothing(
+                    "Delete target node not found.".to_string(),
+                ))?;
+            return delete_at(self, node, maybe_source);
         }
 
-        Ok(Operation::DeleteNode(OpDeleteNode { node }))
+        // whitespace text and nodes that are not elements or text do not count
+        let is_empty = |element: Node| {
+            self.children(element).all(|child| {
+                removed.contains(&child)
+                    || !self.is_element(child)
+                        && self.text_str(child).is_none_or(|t| t.trim().is_empty())
+            })
+        };
+        let predicates: &[Predicate] = match &target.where_clause {
+            Some(where_clause) => &where_clause.predicates,
+            None => &[],
+        };
+        let fits = |element: Node| {
+            predicates.iter().all(|p| match p.relative_to(path) {
+                Some(relative) => self.fits_predicate(element, &relative, bindings),
+                None => self.fits_predicate(node, p, bindings),
+            })
+        };
+
+        self.find_child_elements(node, path)
+            .into_iter()
+            .filter(|n| !removed.contains(n))
+            .filter(|n| !target.empty || is_empty(*n))
+            .filter(|n| fits(*n))
+            .map(|n| delete_at(self, n, maybe_source))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|ops| ops.into_iter().flatten().collect())
     }
 
-    fn get_operations(&self, node: Node, mutation: &Mutation) -> Result<Vec<Operation>, Error> {
+    fn get_operations(
+        &self,
+        node: Node,
+        mutation: &Mutation,
+        bindings: &Bindings,
+        removed: &[Node],
+    ) -> Result<Vec<Operation>, Error> {
         let mut ops = vec![];
 
         if let Some(set_clause) = mutation.set_clause.clone() {
             for ref assignment in set_clause.assignments.into_iter() {
-                ops.push(self.assign(node, assignment)?);
+                ops.append(&mut self.assign(node, assignment, bindings)?);
             }
         }
-        if let Some(ref delete_clause) = mutation.delete_clause.clone() {
-            for path_var in &delete_clause.targets {
-                ops.push(self.delete(node, path_var)?);
+        if let Some(rename_clause) = &mutation.rename_clause {
+            for rename in &rename_clause.renames {
+                ops.extend(self.rename(node, rename));
+            }
+        }
+        if let Some(insert_clause) = &mutation.insert_clause {
+            ops.push(self.insert(node, insert_clause, bindings)?);
+        }
+        if let Some(move_clause) = &mutation.move_clause {
+            ops.append(&mut self.relocate(node, move_clause, bindings)?);
+        }
+        if let Some(wrap_clause) = &mutation.wrap_clause {
+            ops.push(self.wrap_node(node, wrap_clause, bindings)?);
+        }
+        if mutation.unwrap_clause.is_some() {
+            ops.push(self.unwrap_node(node)?);
+        }
+        if let Some(sort_clause) = &mutation.sort_clause {
+            ops.append(&mut self.sort(node, sort_clause, removed));
+        }
+        if let Some(deduplicate_clause) = &mutation.deduplicate_clause {
+            ops.append(&mut self.deduplicate(node, deduplicate_clause));
+        }
+        if let Some(delete_clause) = &mutation.delete_clause {
+            let mut removed: Vec<Node> = removed
+                .iter()
+                .copied()
+                .chain(ops.iter().flat_map(Operation::removed_nodes))
+                .collect();
+            for target in &delete_clause.targets {
+                let mut deleted = self.delete(node, target, bindings, &removed)?;
+                removed.extend(deleted.iter().flat_map(Operation::removed_nodes));
+                ops.append(&mut deleted);
             }
         }
 
         Ok(ops)
     }
 
-    fn get_operations_all(
+    fn get_operations_after(
         &self,
         node: Node,
         mutations: &[&Mutation],
+        bindings: &Bindings,
+        earlier: &[Operation],
     ) -> Result<Vec<Operation>, Error> {
         let mut operations: Vec<Operation> = vec![];
+        let mut removed: Vec<Node> = earlier.iter().flat_map(Operation::removed_nodes).collect();
         for &mutation in mutations {
+            let start = operations.len();
             let nodes: Vec<Node> = self.descendants(node).collect();
             for node in nodes {
-                if self.is_fit(node, mutation) {
-                    let mut ops = self.get_operations(node, mutation)?;
-                    operations.append(&mut ops);
+                let dropped = self.is_dropped(node, earlier) || self.is_dropped(node, &operations);
+                if !dropped && self.is_fit(node, mutation, bindings) {
+                    let ops = self.get_operations(node, mutation, bindings, &removed)?;
+                    removed.extend(ops.iter().flat_map(Operation::removed_nodes));
+                    for op in ops {
+                        // siblings next to each other share their wrappers
+                        match op {
+                            Operation::WrapNode(op)
+                                if join_wrap(self, &mut operations[start..], &op) => {}
+                            op => operations.push(op),
+                        }
+                    }
                 }
             }
         }
@@ -226,14 +1078,58 @@ impl Valueable for Xot {
         Ok(operations)
     }
 
+    fn is_dropped(&self, node: Node, operations: &[Operation]) -> bool {
+        operations.iter().any(|operation| match operation {
+            Operation::DeleteNode(op) | Operation::DeleteDuplicate(op) => {
+                self.ancestors(node).any(|n| n == op.node)
+            }
+            Operation::ReplaceNode(op) => self.ancestors(node).any(|n| n == op.node),
+            // the children stay in the document
+            Operation::UnwrapNode(op) => node == op.node,
+            Operation::SetInner(op) => self.ancestors(node).skip(1).any(|n| n == op.node),
+            _ => false,
+        })
+    }
+
+    fn remove_own_whitespace(&mut self, node: Node) -> Result<(), Error> {
+        let ends_line = self
+            .tail_text_node(node)
+            .and_then(|n| self.text_str(n))
+            .and_then(|t| t.find('\n').map(|i| t[..i].trim().is_empty()))
+            .unwrap_or(false);
+        let previous = match self.previous_sibling(node).filter(|n| self.is_text(*n)) {
+            Some(previous) if ends_line => previous,
+            _ => return Ok(()),
+        };
+        let text = self.text_str(previous).unwrap_or_default();
+        if let Some(i) = text.rfind('\n').filter(|i| text[*i..].trim().is_empty()) {
+            let text = text[..i].to_string();
+            if text.is_empty() {
+                self.remove(previous).map_err(Error::XotError)?;
+            } else {
+                self.text_mut(previous)
+                    .ok_or(Error::NotATextNode)?
+                    .set(text);
+            }
+        }
+        Ok(())
+    }
+
     fn apply(&mut self, operation: &Operation) -> Result<(), Error> {
         match operation {
             Operation::AddSubTree(op) => {
                 let mut node = op.node;
+                let mut indentation = op.indentation.clone();
                 for name in &op.node_path {
                     let name_id = self.add_name(name.as_str());
                     let element_node = self.new_element(name_id);
-                    self.append(node, element_node).map_err(Error::XotError)?;
+                    match &indentation {
+                        Some(ind) => {
+                            append_indented(self, node, element_node, ind)?;
+                            indentation = Some(ind.nested());
+                        }
+                        None => self.append(node, element_node).map_err(Error::XotError)?,
+                    }
                     node = element_node;
                 }
 
@@ -255,6 +1151,11 @@ impl Valueable for Xot {
                         self.insert_after(node, text_node)
                             .map_err(Error::XotError)?;
                     }
+                    SubOperation::AddXml(xml) => {
+                        for child in parse_xml(self, xml)? {
+                            self.append(node, child).map_err(Error::XotError)?;
+                        }
+                    }
                 }
             }
             Operation::SetAttribute(op) => {
@@ -270,6 +1171,28 @@ impl Valueable for Xot {
                     .ok_or(Error::NameNotFound(op.name.clone()))?;
                 self.attributes_mut(op.node).remove(name_id);
             }
+            Operation::RenameAttribute(op) => {
+                let name_id = self
+                    .name(op.name.as_str())
+                    .ok_or(Error::NameNotFound(op.name.clone()))?;
+                let new_name_id = self.add_name(op.new_name.as_str());
+                // attributes are kept in insertion order, so all of them are
+                // inserted again
+                let attributes: Vec<_> = self
+                    .attributes(op.node)
+                    .iter()
+                    .filter(|(id, _)| *id != new_name_id || name_id == new_name_id)
+                    .map(|(id, value)| {
+                        let id = if id == name_id { new_name_id } else { id };
+                        (id, value.clone())
+                    })
+                    .collect();
+                let mut node_attributes = self.attributes_mut(op.node);
+                node_attributes.clear();
+                for (id, value) in attributes {
+                    node_attributes.insert(id, value);
+                }
+            }
             Operation::SetText(op) => {
                 let text = self.text_mut(op.node).ok_or(Error::NotATextNode)?;
                 text.set(op.value.clone());
@@ -287,11 +1210,182 @@ impl Valueable for Xot {
                 let element = self.element_mut(op.node).ok_or(Error::NotAnElement)?;
                 element.set_name(name_id);
             }
-            Operation::DeleteNode(op) => {
-                let parent = self.parent(op.node);
+            Operation::SetInner(op) => {
+                let children: Vec<Node> = self.children(op.node).collect();
+                for child in children {
+                    self.remove(child).map_err(Error::XotError)?;
+                }
+                for child in parse_xml(self, &op.xml)? {
+                    self.append(op.node, child).map_err(Error::XotError)?;
+                }
+            }
+            Operation::ReplaceNode(op) => {
+                for child in parse_xml(self, &op.xml)? {
+                    self.insert_before(op.node, child)
+                        .map_err(Error::XotError)?;
+                }
+                self.remove(op.node).map_err(Error::XotError)?;
+            }
+            Operation::SetNodeValue(op) => {
+                if let Some(comment) = self.comment_mut(op.node) {
+                    comment
+                        .set(format!(" {} ", op.value))
+                        .map_err(Error::XotError)?;
+                } else {
+                    self.processing_instruction_mut(op.node)
+                        .ok_or(Error::NotAnElement)?
+                        .set_data(Some(op.value.as_str()));
+                }
+            }
+            Operation::InsertNode(op) => {
+                let nodes = parse_xml(self, &op.xml)?;
+                place_all(self, &nodes, op.position, op.indentation.as_ref())?;
+            }
+            Operation::InsertElement(op) => {
+                let element = new_element(self, &op.name, &op.attributes);
+                if let Some(text) = &op.text {
+                    self.append_text(element, text).map_err(Error::XotError)?;
+                }
+                place(self, element, op.position, op.indentation.as_ref())?;
+            }
+            Operation::MoveNodes(op) => {
+                for &node in &op.nodes {
+                    if let Some(ind) = &op.indentation {
+                        reindent(self, node, ind)?;
+                    }
+                    self.remove_own_whitespace(node)?;
+                }
+                place_all(self, &op.nodes, op.position, op.indentation.as_ref())?;
+            }
+            Operation::CopyNodes(op) => {
+                let mut copies = vec![];
+                for &node in &op.nodes {
+                    let copy = self.clone_node(node);
+                    if let Some(ind) = &op.indentation {
+                        // the copy has no siblings to tell its indentation
+                        let from = self.line_indentation(node).map(str::to_string);
+                        reindent_from(self, copy, from.as_deref(), &ind.child)?;
+                    }
+                    copies.push(copy);
+                }
+                place_all(self, &copies, op.position, op.indentation.as_ref())?;
+            }
+            Operation::WrapNode(op) => {
+                let wrappers: Vec<Node> = op
+                    .wrappers
+                    .iter()
+                    .map(|(name, attributes)| new_element(self, name, attributes))
+                    .collect();
+                let outermost = *wrappers.first().ok_or(Error::NothingToAdd)?;
+                let first = *op.nodes.first().ok_or(Error::NothingToAdd)?;
+                let last = *op.nodes.last().ok_or(Error::NothingToAdd)?;
+                // the nodes along with the whitespace between them
+                let mut content = vec![first];
+                while let Some(&node) = content.last().filter(|n| **n != last) {
+                    content.push(self.next_sibling(node).ok_or(Error::NotAnElement)?);
+                }
+                self.insert_before(first, outermost)
+                    .map_err(Error::XotError)?;
+                let line = |level: usize| {
+                    op.indentation
+                        .as_ref()
+                        .map(|ind| format!("\n{}{}", ind.child, ind.unit.repeat(level)))
+                };
+                if let Some(deepest) = line(wrappers.len()) {
+                    for node in &content {
+                        reindent_from(
+                            self,
+                            *node,
+                            op.indentation.as_ref().map(|i| i.child.as_str()),
+                            &deepest[1..],
+                        )?;
+                    }
+                }
+                let contents = wrappers[1..].iter().map(|w| vec![*w]).chain([content]);
+                for (level, (wrapper, content)) in wrappers.iter().zip(contents).enumerate() {
+                    if let Some(text) = line(level + 1) {
+                        self.append_text(*wrapper, &text).map_err(Error::XotError)?;
+                    }
+                    for node in content {
+                        self.append(*wrapper, node).map_err(Error::XotError)?;
+                    }
+                    if let Some(text) = line(level) {
+                        self.append_text(*wrapper, &text).map_err(Error::XotError)?;
+                    }
+                }
+            }
+            Operation::UnwrapNode(op) => {
+                if let Some(ind) = &op.indentation {
+                    // the line breaks around the children go with the node
+                    let first = self.first_child(op.node).ok_or(Error::NotATextNode)?;
+                    let last = self.last_child(op.node).ok_or(Error::NotATextNode)?;
+                    let mut edges = vec![first, last];
+                    edges.dedup();
+                    for edge in edges {
+                        let text = self.text_str(edge).ok_or(Error::NotATextNode)?;
+                        let text = match (edge == first, edge == last) {
+                            (true, true) => text.trim(),
+                            (true, false) => text.trim_start(),
+                            _ => text.trim_end(),
+                        };
+                        if text.is_empty() {
+                            self.remove(edge).map_err(Error::XotError)?;
+                        } else {
+                            let text = text.to_string();
+                            self.text_mut(edge).ok_or(Error::NotATextNode)?.set(text);
+                        }
+                    }
+                    reindent_from(self, op.node, Some(&ind.child), &ind.closing)?;
+                }
+                // taken from the front, so moving a child never joins the
+                // text nodes around it
+                let children: Vec<Node> = self.children(op.node).collect();
+                for child in children {
+                    self.insert_before(op.node, child)
+                        .map_err(Error::XotError)?;
+                }
                 self.remove(op.node).map_err(Error::XotError)?;
-                if let Some(node) = parent {
-                    self.remove_insignificant_whitespace(node);
+            }
+            Operation::SortChildren(op) => {
+                let groups = self.element_groups(op.node);
+                let group_of = |element: &Node| {
+                    groups
+                        .iter()
+                        .find(|g| g.last() == Some(element))
+                        .ok_or(Error::NotAnElement)
+                };
+                let mut sorted = op.elements.iter();
+                let mut children = vec![];
+                for group in &groups {
+                    match group.last() {
+                        Some(element) if op.elements.contains(element) => {
+                            let next = sorted.next().ok_or(Error::NotAnElement)?;
+                            children.extend(group_of(next)?);
+                        }
+                        _ => children.extend(group),
+                    }
+                }
+                // taken from the front, so no text nodes are joined
+                for child in &children {
+                    self.detach(*child).map_err(Error::XotError)?;
+                }
+                for child in children {
+                    self.append(op.node, child).map_err(Error::XotError)?;
+                }
+            }
+            Operation::DeleteNode(op) | Operation::DeleteDuplicate(op) => {
+                if self.preserves_space(op.node) {
+                    self.remove(op.node).map_err(Error::XotError)?;
+                } else if self.is_only_child(op.node) {
+                    // nothing but whitespace would be left, empty the parent
+                    let parent = self.parent(op.node).ok_or(Error::NotAnElement)?;
+                    let children: Vec<Node> = self.children(parent).collect();
+                    for child in children {
+                        self.remove(child).map_err(Error::XotError)?;
+                    }
+                } else {
+                    self.remove_own_whitespace(op.node)?;
+                    self.remove(op.node).map_err(Error::XotError)?;
                 }
             }
         }